@@ -42,6 +42,26 @@ pub enum HitZone {
     RightFoot,
 }
 
+impl HitZone {
+    /// Every zone, head to feet.
+    pub const ALL: [HitZone; 14] = [
+        HitZone::Head,
+        HitZone::LeftHand,
+        HitZone::RightHand,
+        HitZone::LeftArm,
+        HitZone::RightArm,
+        HitZone::Shoulders,
+        HitZone::Chest,
+        HitZone::Stomach,
+        HitZone::Vitals,
+        HitZone::Thighs,
+        HitZone::LeftLeg,
+        HitZone::RightLeg,
+        HitZone::LeftFoot,
+        HitZone::RightFoot,
+    ];
+}
+
 impl FromStr for HitZone {
     type Err = String;
 
@@ -109,6 +129,7 @@ impl fmt::Display for Armor {
 }
 
 impl Armor {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         name: String,
         amount: i32,
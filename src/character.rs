@@ -1,8 +1,14 @@
 use crate::advantages::{
     Advantage, ModifierTarget, TAG_BRUISE_SCALE, TAG_HEALING_RATE, TAG_INITIATIVE,
 };
-use crate::dice::{skill_check, CheckResult, DieRoller, Difficulty};
+use crate::combat::{AimingState, UNFAMILIAR_WEAPON_SHIFT, WEAPON_FAMILIARITY_USES};
+use crate::dice::{
+    open_roll, skill_check, skill_check_probabilities, CheckModifier, CheckProbabilities,
+    CheckResult, DieRoller, Difficulty, ModifierSource, OpenRollResult, Situation,
+};
 use crate::health::WoundState;
+use crate::skill_tree::SkillTree;
+use crate::weapons::{Ammunition, Malfunction, Weapon};
 use crate::{armor::HitZone, Armor};
 use crate::{inventory::Inventory, DamageType};
 use serde::{Deserialize, Serialize};
@@ -31,12 +37,27 @@ pub struct Character {
     /// [`Character::start_session`] regenerates half the current base LUCK
     /// (rounded up). See [`Character::start_session`] for the three luck levels.
     pub current_luck: i32,
+    /// Actions declared for the current round beyond the first
+    /// (see [`Character::declare_actions`]).
+    #[serde(default)]
+    pub extra_actions: i32,
     pub damage_notes: String,
     pub worn_armor: Vec<Uuid>,
+    /// Weapon models (item names) or whole categories the character knows;
+    /// others of a trained class are harder to use (Q25).
+    #[serde(default)]
+    pub familiar_weapons: Vec<String>,
     pub skills: Vec<Skill>,
     pub advantages: Vec<Advantage>,
     pub attributes: Attributes,
     pub inventory: Inventory,
+    /// Aiming bonus built up and ambush prepared for the next shot.
+    #[serde(default)]
+    pub aiming: AimingState,
+    /// Checks made so far with weapons that aren't familiar yet, by name
+    /// (see [`Character::practice_weapon`]).
+    #[serde(default)]
+    pub weapon_practice: BTreeMap<String, i32>,
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize, PartialOrd, Ord)]
@@ -160,6 +181,7 @@ pub struct HitOutcome {
 }
 
 impl Character {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         name: String,
         role: String,
@@ -181,11 +203,15 @@ impl Character {
             attributes: Attributes(BTreeMap::new()),
             inventory: Inventory::new(),
             worn_armor: Vec::new(),
+            familiar_weapons: Vec::new(),
             current_damage: 0,
             current_bruise: 0,
             pending_roll_malus: 0,
             healing_progress: 0,
             current_luck: luck,
+            extra_actions: 0,
+            aiming: AimingState::default(),
+            weapon_practice: BTreeMap::new(),
             damage_notes: "".to_string(),
             skills: Vec::new(),
             advantages: Vec::new(),
@@ -275,7 +301,7 @@ Character {{ \n\
     /// Body Type Modifier
     /// How much damage gets reduced when being hit, based on the body stat.
     pub fn btm(&self) -> i32 {
-        match self.attributes.get(&Attribute::Body).unwrap().actual {
+        match self.sheet_attribute(Attribute::Body) {
             ..=2 => 0,
             3..=4 => 1,
             5..=7 => 2,
@@ -285,6 +311,21 @@ Character {{ \n\
         }
     }
 
+    /// Damage modifier (DAM) added to melee and martial arts damage, from
+    /// the sheet BODY like [`Character::btm`].
+    pub fn dam(&self) -> i32 {
+        match self.sheet_attribute(Attribute::Body) {
+            ..=2 => -2,
+            3..=4 => -1,
+            5..=7 => 0,
+            8..=9 => 1,
+            10 => 2,
+            11..=12 => 4,
+            13..=14 => 6,
+            _ => 8,
+        }
+    }
+
     /// The character's wound state, derived from the current damage.
     pub fn wound_state(&self) -> WoundState {
         WoundState::from_damage(self.current_damage)
@@ -307,6 +348,24 @@ Character {{ \n\
         self.modifier_sum(|target| matches!(target, ModifierTarget::Tag(t) if t == tag))
     }
 
+    /// The advantage modifiers matching `matches`, one entry per advantage.
+    fn advantage_modifiers(&self, matches: impl Fn(&ModifierTarget) -> bool) -> Vec<CheckModifier> {
+        self.advantages
+            .iter()
+            .filter_map(|advantage| {
+                let value: i32 = advantage
+                    .modifiers
+                    .iter()
+                    .filter(|modifier| matches(&modifier.target))
+                    .map(|modifier| modifier.value)
+                    .sum();
+                (value != 0).then(|| {
+                    CheckModifier::new(ModifierSource::Advantage(advantage.name.clone()), value)
+                })
+            })
+            .collect()
+    }
+
     fn modifier_sum(&self, matches: impl Fn(&ModifierTarget) -> bool) -> i32 {
         self.advantages
             .iter()
@@ -325,6 +384,7 @@ Character {{ \n\
     /// Rolls initiative: 1d10 + effective REF, plus advantage modifiers on the
     /// `initiative` tag (Kampfreflexe).
     pub fn roll_initiative(&self, roller: &mut dyn DieRoller) -> i32 {
+        roller.annotate(&format!("{}: initiative", self.name));
         roller.d10()
             + self.effective_attribute(Attribute::Reflexes)
             + self.modifier_for_tag(TAG_INITIATIVE)
@@ -366,10 +426,39 @@ Character {{ \n\
     /// means recovery. On a critical failure the GM decides — usually out
     /// for longer.
     pub fn ko_check(&self, roller: &mut dyn DieRoller) -> CheckResult {
-        let body = self.attributes.get(&Attribute::Body).unwrap().actual
-            + self.modifier_for_attribute(Attribute::Body)
-            - self.wound_state().ko_malus();
-        skill_check(body, 0, 0, Difficulty::Custom(10), roller)
+        self.ko_check_with(&Situation::new(), roller)
+    }
+
+    /// [`Character::ko_check`] with the situation's bonuses on the roll
+    /// (e.g. −2 after being thrown), its difficulty shifts on the target and
+    /// the advantage modifiers on its tags.
+    pub fn ko_check_with(&self, situation: &Situation, roller: &mut dyn DieRoller) -> CheckResult {
+        let mut modifiers = vec![CheckModifier::new(
+            ModifierSource::Attribute(Attribute::Body.to_string()),
+            self.sheet_attribute(Attribute::Body),
+        )];
+        modifiers.extend(self.advantage_modifiers(|target| {
+            matches!(target, ModifierTarget::Attribute(Attribute::Body))
+        }));
+        let wound_state = self.wound_state();
+        if wound_state.ko_malus() != 0 {
+            modifiers.push(CheckModifier::new(
+                ModifierSource::WoundState(wound_state.to_string()),
+                -wound_state.ko_malus(),
+            ));
+        }
+        modifiers.extend(self.situation_bonuses(situation));
+        let difficulty = situation.shifted(Difficulty::Custom(10));
+        roller.annotate(&format!(
+            "{}: KO check vs {}",
+            self.name,
+            difficulty.target()
+        ));
+        let body = modifiers.iter().map(|modifier| modifier.value).sum();
+        let mut result = skill_check(body, 0, 0, difficulty, roller);
+        result.modifiers = modifiers;
+        result.difficulty_shifts = situation.difficulty_shifts.clone();
+        result
     }
 
     /// The morning-after complication check: BODY against 10 + current damage.
@@ -385,13 +474,27 @@ Character {{ \n\
             return None;
         }
         let difficulty = Difficulty::Custom(10 + self.current_damage);
-        Some(skill_check(
+        roller.annotate(&format!(
+            "{}: complication check vs {}",
+            self.name,
+            difficulty.target()
+        ));
+        let mut result = skill_check(
             self.effective_attribute(Attribute::Body),
             0,
             0,
             difficulty,
             roller,
-        ))
+        );
+        result.modifiers = self.attribute_breakdown(Attribute::Body);
+        Some(result)
+    }
+
+    /// The attribute as written on the sheet, without advantages, wounds or
+    /// encumbrance. Derived stats (BTM, DAM, carry capacity) use this value,
+    /// so wounds and a heavy pack don't shrink them.
+    pub fn sheet_attribute(&self, attr: Attribute) -> i32 {
+        self.attributes[&attr].actual
     }
 
     /// Returns the effective attribute value for dice rolls, including all
@@ -401,19 +504,61 @@ Character {{ \n\
     /// modify that, encumbrance maluses are subtracted afterwards, the result
     /// never drops below 0 (see Q19).
     pub fn effective_attribute(&self, attr: Attribute) -> i32 {
-        let mut value = self.wound_state().modify_attribute(
-            attr,
-            self.attributes[&attr].actual + self.modifier_for_attribute(attr),
-        );
+        self.attribute_breakdown(attr)
+            .iter()
+            .map(|modifier| modifier.value)
+            .sum()
+    }
 
+    /// [`Character::effective_attribute`], itemized: the sheet value first,
+    /// then one entry per modifier that changes it (zero entries are left
+    /// out). The wound penalty is recorded as the difference it makes, since
+    /// halving and thirding depend on the value they apply to.
+    pub fn attribute_breakdown(&self, attr: Attribute) -> Vec<CheckModifier> {
+        let sheet = self.sheet_attribute(attr);
+        let mut modifiers = vec![CheckModifier::new(
+            ModifierSource::Attribute(attr.to_string()),
+            sheet,
+        )];
+        modifiers.extend(self.advantage_modifiers(
+            |target| matches!(target, ModifierTarget::Attribute(a) if *a == attr),
+        ));
+
+        let unwounded = sheet + self.modifier_for_attribute(attr);
+        let wound_state = self.wound_state();
+        let mut value = wound_state.modify_attribute(attr, unwounded);
+        if value != unwounded {
+            modifiers.push(CheckModifier::new(
+                ModifierSource::WoundState(wound_state.to_string()),
+                value - unwounded,
+            ));
+        }
+
+        let mut encumbrances = Vec::new();
         match attr {
             Attribute::Reflexes => {
-                value -= self.encumberance() + self.calculate_armor_encumberance()
+                encumbrances.push((ModifierSource::Encumbrance, self.encumberance()));
+                encumbrances.push((
+                    ModifierSource::ArmorEncumbrance,
+                    self.calculate_armor_encumberance(),
+                ));
+            }
+            Attribute::Move => {
+                encumbrances.push((ModifierSource::Encumbrance, self.encumberance()))
             }
-            Attribute::Move => value -= self.encumberance(),
             _ => {}
         }
-        value.max(0)
+        for (source, malus) in encumbrances {
+            if malus != 0 {
+                modifiers.push(CheckModifier::new(source, -malus));
+                value -= malus;
+            }
+        }
+
+        if value < 0 {
+            modifiers.push(CheckModifier::new(ModifierSource::AttributeFloor, -value));
+        }
+        modifiers
     }
 
     /// Calculates the malus to movement and reflexes based on encumberance
@@ -433,7 +578,7 @@ Character {{ \n\
     /// Looks up the carry capacity of the character
     /// Returns grams.
     pub fn carry_capacity(&self) -> i32 {
-        self.attributes.get(&Attribute::Body).unwrap().actual.max(0) * 10000
+        self.sheet_attribute(Attribute::Body).max(0) * 10000
     }
 
     /// Looks up the deadlift capacity of the character
@@ -465,6 +610,174 @@ Character {{ \n\
         }
     }
 
+    /// Loads the weapon `weapon_uuid` from the ammunition item
+    /// `ammunition_uuid` in the inventory: `rounds` of them, or as many as
+    /// fit if `None`. The rounds are taken from the ammunition item, which
+    /// leaves the inventory once empty. Returns how many rounds were loaded.
+    pub fn reload(
+        &mut self,
+        weapon_uuid: Uuid,
+        ammunition_uuid: Uuid,
+        rounds: Option<i32>,
+    ) -> Result<i32, String> {
+        let ammunition = self
+            .inventory
+            .get_item(ammunition_uuid)
+            .and_then(|item| item.as_any().downcast_ref::<Ammunition>())
+            .cloned()
+            .ok_or_else(|| {
+                format!(
+                    "Character '{}' carries no ammunition with id {}",
+                    self.name, ammunition_uuid
+                )
+            })?;
+        let weapon = self.weapon_mut(weapon_uuid)?;
+        let wanted = rounds.unwrap_or(ammunition.count()).min(ammunition.count());
+        let loaded = weapon.load(&ammunition, wanted)?;
+
+        let remaining = ammunition.count() - loaded;
+        if remaining == 0 {
+            self.inventory.remove(ammunition_uuid);
+        } else if let Some(item) = self.inventory.get_item_mut(ammunition_uuid) {
+            item.get_item_mut().amount = remaining;
+        }
+        Ok(loaded)
+    }
+
+    /// Empties the magazine of the weapon `weapon_uuid` back into the
+    /// inventory: each kind of round joins an ammunition item of the same
+    /// caliber and damage type, or becomes a new one (weightless and
+    /// priceless, as the magazine doesn't know either). Returns how many
+    /// rounds were unloaded.
+    pub fn unload(&mut self, weapon_uuid: Uuid) -> Result<i32, String> {
+        let unloaded = self.weapon_mut(weapon_uuid)?.unload();
+        let mut total = 0;
+        for rounds in unloaded {
+            total += rounds.count;
+            let matching = self
+                .inventory
+                .get_all_ammunition()
+                .into_iter()
+                .find(|ammunition| {
+                    ammunition.caliber == rounds.caliber
+                        && ammunition.damage_type == rounds.damage_type
+                })
+                .map(|ammunition| ammunition.item.uuid);
+            match matching.and_then(|uuid| self.inventory.get_item_mut(uuid)) {
+                Some(item) => item.get_item_mut().amount += rounds.count,
+                None => self.inventory.push(Box::new(Ammunition::new(
+                    format!("{} {}", rounds.caliber, rounds.damage_type),
+                    rounds.caliber,
+                    rounds.damage_type,
+                    rounds.count,
+                    0,
+                    0,
+                ))),
+            }
+        }
+        Ok(total)
+    }
+
+    /// Whether the character knows `weapon`: its model (item name) or its
+    /// category is on the familiar list.
+    pub fn is_familiar_with(&self, weapon: &Weapon) -> bool {
+        let category = weapon.category.to_string();
+        self.familiar_weapons
+            .iter()
+            .any(|familiar| *familiar == weapon.item.name || *familiar == category)
+    }
+
+    /// Puts a weapon model or category on the familiar list, by GM fiat or
+    /// through practice. Returns false if it already was on it.
+    pub fn learn_weapon(&mut self, name: &str) -> bool {
+        self.weapon_practice.remove(name);
+        if self
+            .familiar_weapons
+            .iter()
+            .any(|familiar| familiar == name)
+        {
+            return false;
+        }
+        self.familiar_weapons.push(name.to_string());
+        true
+    }
+
+    /// The difficulty shift for a check with `weapon` (Q25): +3 if the
+    /// character is trained in its skill but not familiar with the weapon.
+    /// `None` without the skill: attacks need it and fail before any check.
+    pub fn unfamiliar_weapon_shift(&self, weapon: &Weapon) -> Option<CheckModifier> {
+        if self.is_familiar_with(weapon) || self.skill_level(&weapon.skill).is_none() {
+            return None;
+        }
+        Some(CheckModifier::new(
+            ModifierSource::UnfamiliarWeapon(weapon.item.name.clone()),
+            UNFAMILIAR_WEAPON_SHIFT,
+        ))
+    }
+
+    /// Counts a check made with `weapon` towards familiarity: after
+    /// [`WEAPON_FAMILIARITY_USES`] checks the model is learned. Returns true
+    /// when this use made it familiar.
+    pub fn practice_weapon(&mut self, weapon: &Weapon) -> bool {
+        if self.unfamiliar_weapon_shift(weapon).is_none() {
+            return false;
+        }
+        let uses = self
+            .weapon_practice
+            .entry(weapon.item.name.clone())
+            .or_insert(0);
+        *uses += 1;
+        if *uses < WEAPON_FAMILIARITY_USES {
+            return false;
+        }
+        self.learn_weapon(&weapon.item.name)
+    }
+
+    /// Clears the malfunction of the weapon `weapon_uuid`. A misfire only
+    /// takes the action and returns `None`; a jam needs a normal (15) Tech
+    /// check, whose result is returned — the weapon stays jammed if it fails.
+    pub fn clear_malfunction(
+        &mut self,
+        weapon_uuid: Uuid,
+        luck: i32,
+        roller: &mut dyn DieRoller,
+    ) -> Result<Option<CheckResult>, String> {
+        let weapon = self.weapon_mut(weapon_uuid)?;
+        match weapon.malfunction {
+            None => Err(format!("Weapon '{}' has no malfunction", weapon.item.name)),
+            Some(Malfunction::Misfire) => {
+                weapon.malfunction = None;
+                Ok(None)
+            }
+            Some(Malfunction::Jammed) => {
+                let check =
+                    self.check_attribute(Attribute::Tech, luck, Difficulty::Normal, roller)?;
+                if check.outcome.is_success() {
+                    self.weapon_mut(weapon_uuid)?.malfunction = None;
+                }
+                Ok(Some(check))
+            }
+        }
+    }
+
+    /// The carried [`Weapon`] with `weapon_uuid`; attacks, reloading and
+    /// unloading all look their weapon up here.
+    pub(crate) fn weapon(&self, weapon_uuid: Uuid) -> Result<&Weapon, String> {
+        self.inventory
+            .get_item(weapon_uuid)
+            .and_then(|item| item.as_any().downcast_ref::<Weapon>())
+            .ok_or_else(|| no_weapon(&self.name, weapon_uuid))
+    }
+
+    /// Mutable counterpart of [`Character::weapon`].
+    pub(crate) fn weapon_mut(&mut self, weapon_uuid: Uuid) -> Result<&mut Weapon, String> {
+        let name = &self.name;
+        self.inventory
+            .get_item_mut(weapon_uuid)
+            .and_then(|item| item.as_any_mut().downcast_mut::<Weapon>())
+            .ok_or_else(|| no_weapon(name, weapon_uuid))
+    }
+
     /// Hit the character with some damage
     ///
     /// This will apply damage to the armor (outer to inner) and then to the character.
@@ -505,6 +818,7 @@ Character {{ \n\
         } else if is_gunshot && remaining_damage > 4 {
             // House rule: a gunshot doing more than 4 damage rolls 1d10 — that
             // is the maximum extra damage before the shot exits through the back.
+            roller.annotate(&format!("{}: penetration cap", self.name));
             let cap = 4 + roller.d10();
             if remaining_damage > cap {
                 remaining_damage = cap;
@@ -629,6 +943,14 @@ Character {{ \n\
     /// Returns an error when the pool doesn't cover it (or `points` is negative);
     /// the pool is unchanged in that case.
     pub fn spend_luck(&mut self, points: i32) -> Result<(), String> {
+        self.check_luck(points)?;
+        self.current_luck -= points;
+        Ok(())
+    }
+
+    /// Checks whether the pool covers spending `points`, without spending
+    /// them. Same errors as [`Character::spend_luck`].
+    pub fn check_luck(&self, points: i32) -> Result<(), String> {
         if points < 0 {
             return Err(format!(
                 "Cannot spend a negative amount of luck: {}",
@@ -641,7 +963,6 @@ Character {{ \n\
                 self.name, self.current_luck, points
             ));
         }
-        self.current_luck -= points;
         Ok(())
     }
 
@@ -702,30 +1023,153 @@ Character {{ \n\
         difficulty: Difficulty,
         roller: &mut dyn DieRoller,
     ) -> Result<CheckResult, String> {
-        let skill = self
-            .skills
-            .iter()
-            .find(|skill| skill.name == skill_name)
-            .ok_or_else(|| {
-                format!(
-                    "Character '{}' has no skill named '{}'",
-                    self.name, skill_name
-                )
-            })?;
-        let (attribute_value, skill_level) = (self.effective_attribute(skill.base), skill.level);
-        let advantage_bonus = self.modifier_for_skill(skill_name);
+        self.check_skill_with(skill_name, luck, difficulty, &Situation::new(), roller)
+    }
+
+    /// [`Character::check_skill`] under the given circumstances: the
+    /// situation's bonuses go on the roll, its difficulty shifts on the
+    /// target, and advantage modifiers on its tags are applied.
+    pub fn check_skill_with(
+        &mut self,
+        skill_name: &str,
+        luck: i32,
+        difficulty: Difficulty,
+        situation: &Situation,
+        roller: &mut dyn DieRoller,
+    ) -> Result<CheckResult, String> {
+        self.roll_check(
+            &CheckSpec::Skill(skill_name.to_string()),
+            luck,
+            situation,
+            difficulty,
+            roller,
+        )
+    }
+
+    /// Common core of all checks against a difficulty: looks up the values
+    /// for `spec`, applies the `situation`, spends the committed luck and
+    /// consumes the pending bruise malus. The result carries every modifier,
+    /// itemized.
+    pub(crate) fn roll_check(
+        &mut self,
+        spec: &CheckSpec,
+        luck: i32,
+        situation: &Situation,
+        difficulty: Difficulty,
+        roller: &mut dyn DieRoller,
+    ) -> Result<CheckResult, String> {
+        let mut modifiers = self.check_modifiers(spec)?;
+        let (attribute_value, skill_level) = split_check_values(&modifiers);
         self.spend_luck(luck)?;
         // pure bruise damage puts a malus on the NEXT roll — consume it
-        let malus = std::mem::take(&mut self.pending_roll_malus);
-        Ok(skill_check(
-            attribute_value + advantage_bonus - malus,
+        self.pending_roll_malus = 0;
+
+        let bonuses = self.situation_bonuses(situation);
+        let difficulty = situation.shifted(difficulty);
+        roller.annotate(&format!(
+            "{}: {} vs {}",
+            self.name,
+            spec,
+            difficulty.target()
+        ));
+        let bonus: i32 = bonuses.iter().map(|modifier| modifier.value).sum();
+        let mut result = skill_check(
+            attribute_value + bonus,
             skill_level,
             luck,
             difficulty,
             roller,
+        );
+        modifiers.extend(bonuses);
+        if luck != 0 {
+            modifiers.push(CheckModifier::new(ModifierSource::Luck, luck));
+        }
+        result.modifiers = modifiers;
+        result.difficulty_shifts = situation.difficulty_shifts.clone();
+        Ok(result)
+    }
+
+    /// The situation's bonuses plus the character's advantage modifiers for
+    /// its tags.
+    fn situation_bonuses(&self, situation: &Situation) -> Vec<CheckModifier> {
+        let mut bonuses = situation.bonuses.clone();
+        for tag in &situation.tags {
+            bonuses.extend(self.advantage_modifiers(
+                |target| matches!(target, ModifierTarget::Tag(t) if t == tag),
+            ));
+        }
+        bonuses
+    }
+
+    /// The odds of a check on `skill_name` with `luck` committed, given the
+    /// character's current state (wounds, encumbrance, advantages, pending
+    /// bruise malus). Nothing is rolled or spent.
+    pub fn skill_check_odds(
+        &self,
+        skill_name: &str,
+        luck: i32,
+        difficulty: Difficulty,
+    ) -> Result<CheckProbabilities, String> {
+        let (attribute_value, skill_level) =
+            self.check_values(&CheckSpec::Skill(skill_name.to_string()))?;
+        Ok(skill_check_probabilities(
+            attribute_value,
+            skill_level,
+            luck,
+            difficulty,
+        ))
+    }
+
+    /// The fewest luck points to commit on `skill_name` for a success chance
+    /// of at least `chance` (0.0–1.0).
+    ///
+    /// Returns an error when even the whole current luck pool doesn't get
+    /// there — the advisor never suggests more than the character has.
+    pub fn luck_for_chance(
+        &self,
+        skill_name: &str,
+        difficulty: Difficulty,
+        chance: f64,
+    ) -> Result<i32, String> {
+        for luck in 0..=self.current_luck {
+            let odds = self.skill_check_odds(skill_name, luck, difficulty)?;
+            if odds.success_chance() >= chance {
+                return Ok(luck);
+            }
+        }
+        Err(format!(
+            "Character '{}' can't reach a {:.0}% chance on '{}' vs {} with {} luck points",
+            self.name,
+            chance * 100.0,
+            skill_name,
+            difficulty.target(),
+            self.current_luck
         ))
     }
 
+    /// The luck points to commit on `skill_name` so that no roll is needed
+    /// (auto-success). Returns an error when the current pool is too small.
+    pub fn luck_for_auto_success(
+        &self,
+        skill_name: &str,
+        difficulty: Difficulty,
+    ) -> Result<i32, String> {
+        let (attribute_value, skill_level) =
+            self.check_values(&CheckSpec::Skill(skill_name.to_string()))?;
+        let needed = (difficulty.target() - attribute_value - skill_level).max(0);
+        if needed > self.current_luck {
+            return Err(format!(
+                "Character '{}' needs {} luck points for an auto-success on '{}' vs {}, but has only {}",
+                self.name,
+                needed,
+                skill_name,
+                difficulty.target(),
+                self.current_luck
+            ));
+        }
+        Ok(needed)
+    }
+
     /// Rolls a check on a bare attribute (untrained, skill level 0).
     /// Committed luck is deducted like in [`Character::check_skill`].
     pub fn check_attribute(
@@ -735,16 +1179,232 @@ Character {{ \n\
         difficulty: Difficulty,
         roller: &mut dyn DieRoller,
     ) -> Result<CheckResult, String> {
-        let attribute_value = self.effective_attribute(attribute);
-        self.spend_luck(luck)?;
-        let malus = std::mem::take(&mut self.pending_roll_malus);
-        Ok(skill_check(
-            attribute_value - malus,
-            0,
+        self.check_attribute_with(attribute, luck, difficulty, &Situation::new(), roller)
+    }
+
+    /// [`Character::check_attribute`] under the given circumstances, see
+    /// [`Character::check_skill_with`].
+    pub fn check_attribute_with(
+        &mut self,
+        attribute: Attribute,
+        luck: i32,
+        difficulty: Difficulty,
+        situation: &Situation,
+        roller: &mut dyn DieRoller,
+    ) -> Result<CheckResult, String> {
+        self.roll_check(
+            &CheckSpec::Attribute(attribute),
             luck,
+            situation,
             difficulty,
             roller,
-        ))
+        )
+    }
+
+    /// Rolls openly (no difficulty, see [`open_roll`]) on a skill or a bare
+    /// attribute — the building block of opposed rolls. Effective values,
+    /// advantage bonus, committed luck and the pending bruise malus are
+    /// handled like in [`Character::check_skill`].
+    pub fn open_check(
+        &mut self,
+        spec: &CheckSpec,
+        luck: i32,
+        roller: &mut dyn DieRoller,
+    ) -> Result<OpenRollResult, String> {
+        self.open_check_with(spec, luck, &Situation::new(), roller)
+    }
+
+    /// [`Character::open_check`] with the situation's bonuses (and the
+    /// advantage modifiers for its tags) on the roll. An open roll has no
+    /// target to raise: difficulty shifts count against the total instead.
+    pub fn open_check_with(
+        &mut self,
+        spec: &CheckSpec,
+        luck: i32,
+        situation: &Situation,
+        roller: &mut dyn DieRoller,
+    ) -> Result<OpenRollResult, String> {
+        let (attribute_value, skill_level) = self.check_values(spec)?;
+        self.spend_luck(luck)?;
+        self.pending_roll_malus = 0;
+        let bonus: i32 = self
+            .situation_bonuses(situation)
+            .iter()
+            .map(|modifier| modifier.value)
+            .sum();
+        let shift: i32 = situation
+            .difficulty_shifts
+            .iter()
+            .map(|modifier| modifier.value)
+            .sum();
+        roller.annotate(&format!("{}: {} (open)", self.name, spec));
+        let mut result = open_roll(attribute_value + bonus - shift, skill_level, luck, roller);
+        result.difficulty_shifts = situation.difficulty_shifts.clone();
+        Ok(result)
+    }
+
+    /// The character's skill `skill_name` as checks roll it: the entry that
+    /// provides the level, and the level. The general skill of a
+    /// [`SkillTree`] stops at the split level; a specialization rolls on the
+    /// better of its own level and the general one, so it can be used before
+    /// it is ever bought.
+    pub fn skill_level(&self, skill_name: &str) -> Option<(&Skill, i32)> {
+        let own = self
+            .skills
+            .iter()
+            .find(|skill| skill.name == skill_name)
+            .map(|skill| (skill, skill.level));
+        let tree = match SkillTree::of(skill_name) {
+            Some(tree) => tree,
+            None => return own,
+        };
+        let general = self
+            .skills
+            .iter()
+            .find(|skill| skill.name == tree.general)
+            .map(|skill| (skill, skill.level.min(tree.split_level)));
+        if skill_name == tree.general {
+            return general;
+        }
+        match (own, general) {
+            (Some(own), Some(general)) if general.1 > own.1 => Some(general),
+            (own, general) => own.or(general),
+        }
+    }
+
+    /// [`Character::skill_level`] for rules that need the skill: an error
+    /// naming it if the character doesn't have it.
+    pub(crate) fn trained_skill(&self, skill_name: &str) -> Result<(&Skill, i32), String> {
+        self.skill_level(skill_name).ok_or_else(|| {
+            format!(
+                "Character '{}' has no skill named '{}'",
+                self.name, skill_name
+            )
+        })
+    }
+
+    /// Buys the next level of `skill_name` in play and returns its price in
+    /// CP: target level × 10 (Q32). The general skill of a [`SkillTree`]
+    /// stops at the split level; above it, every specialization is raised
+    /// on its own, continuing from the general level.
+    pub fn raise_skill(&mut self, skill_name: &str) -> Result<i32, String> {
+        let (skill, current) = self.trained_skill(skill_name)?;
+        let (base, level_up_modifier) = (skill.base, skill.level_up_modifier);
+        if let Some(tree) = SkillTree::of(skill_name) {
+            if skill_name == tree.general && current >= tree.split_level {
+                return Err(format!(
+                    "'{}' stops at level {}, raise one of {} instead",
+                    tree.general,
+                    tree.split_level,
+                    tree.specializations.join(", ")
+                ));
+            }
+            if skill_name != tree.general && current < tree.split_level {
+                return Err(format!(
+                    "'{}' can only be raised once '{}' reaches level {}",
+                    skill_name, tree.general, tree.split_level
+                ));
+            }
+        }
+        let target = current + 1;
+        match self
+            .skills
+            .iter_mut()
+            .find(|skill| skill.name == skill_name)
+        {
+            Some(skill) => skill.level = target,
+            None => self.skills.push(Skill::new(
+                skill_name.to_string(),
+                base,
+                target,
+                level_up_modifier,
+            )),
+        }
+        Ok(target * 10)
+    }
+
+    /// The attribute and skill values a check on `spec` would roll with
+    /// right now: effective attribute plus advantage bonus minus the pending
+    /// bruise malus and the multiple-actions malus, and the skill level
+    /// (0 for bare attributes).
+    pub(crate) fn check_values(&self, spec: &CheckSpec) -> Result<(i32, i32), String> {
+        Ok(split_check_values(&self.check_modifiers(spec)?))
+    }
+
+    /// Everything that goes into a check on `spec` right now, itemized:
+    /// the attribute breakdown, the skill level and its advantage modifiers,
+    /// the pending bruise malus and the multiple-actions malus.
+    pub fn check_modifiers(&self, spec: &CheckSpec) -> Result<Vec<CheckModifier>, String> {
+        let mut modifiers = match spec {
+            CheckSpec::Skill(skill_name) => {
+                let (skill, level) = self.trained_skill(skill_name)?;
+                let mut modifiers = self.attribute_breakdown(skill.base);
+                modifiers.push(CheckModifier::new(
+                    ModifierSource::Skill(skill.name.clone()),
+                    level,
+                ));
+                modifiers.extend(self.advantage_modifiers(
+                    |target| matches!(target, ModifierTarget::Skill(s) if s == skill_name),
+                ));
+                modifiers
+            }
+            CheckSpec::Attribute(attribute) => self.attribute_breakdown(*attribute),
+        };
+        if self.pending_roll_malus != 0 {
+            modifiers.push(CheckModifier::new(
+                ModifierSource::PendingRollMalus,
+                -self.pending_roll_malus,
+            ));
+        }
+        if self.action_malus() != 0 {
+            modifiers.push(CheckModifier::new(
+                ModifierSource::MultipleActions,
+                -self.action_malus(),
+            ));
+        }
+        Ok(modifiers)
+    }
+
+    /// Declares how many actions the character takes this round. Every
+    /// extra action puts −3 on ALL checks of the round (house rule), until
+    /// [`Character::end_round`].
+    ///
+    /// `shots_per_round` is the cap of the weapon used, if any: no matter how
+    /// many actions are spent on it, a weapon can't fire more often per round
+    /// than its type allows.
+    pub fn declare_actions(
+        &mut self,
+        actions: i32,
+        shots_per_round: Option<i32>,
+    ) -> Result<(), String> {
+        if actions < 1 {
+            return Err(format!(
+                "Character '{}' must declare at least one action, got {}",
+                self.name, actions
+            ));
+        }
+        if let Some(shots) = shots_per_round {
+            if actions > shots {
+                return Err(format!(
+                    "Character '{}' can't take {} actions with a weapon firing {} shots per round",
+                    self.name, actions, shots
+                ));
+            }
+        }
+        self.extra_actions = actions - 1;
+        Ok(())
+    }
+
+    /// The malus every check carries this round: 3 per extra declared action.
+    pub fn action_malus(&self) -> i32 {
+        3 * self.extra_actions
+    }
+
+    /// Ends the combat round: declared actions (and their malus) and a
+    /// prepared ambush expire.
+    pub fn end_round(&mut self) {
+        self.extra_actions = 0;
+        self.aiming.end_round();
     }
 
     pub fn print_skills(&self) {
@@ -757,6 +1417,42 @@ Character {{ \n\
     }
 }
 
+fn no_weapon(name: &str, weapon_uuid: Uuid) -> String {
+    format!(
+        "Character '{}' carries no weapon with id {}",
+        name, weapon_uuid
+    )
+}
+
+/// Splits itemized check modifiers into the (attribute side, skill level)
+/// pair [`skill_check`] takes.
+fn split_check_values(modifiers: &[CheckModifier]) -> (i32, i32) {
+    let skill_level: i32 = modifiers
+        .iter()
+        .filter(|modifier| matches!(modifier.source, ModifierSource::Skill(_)))
+        .map(|modifier| modifier.value)
+        .sum();
+    let total: i32 = modifiers.iter().map(|modifier| modifier.value).sum();
+    (total - skill_level, skill_level)
+}
+
+/// What a check is rolled on: one of the character's skills (by name) or a
+/// bare attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckSpec {
+    Skill(String),
+    Attribute(Attribute),
+}
+
+impl fmt::Display for CheckSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckSpec::Skill(skill_name) => write!(f, "{}", skill_name),
+            CheckSpec::Attribute(attribute) => write!(f, "{}", attribute),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Skill {
     pub name: String,
@@ -824,11 +1520,36 @@ impl fmt::Display for List {
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use super::*;
     use crate::armor::tests::*;
     use crate::inventory::Item;
-    use toml;
+
+    /// A Solo with BODY 10 (BTM 4, DAM +2), REF 8, MOV 6 and 5 in everything
+    /// else — the stock fighter of the combat tests.
+    pub fn solo(name: &str) -> Character {
+        Character::new(
+            name.to_string(),
+            "Solo".to_string(),
+            25,
+            5,
+            6,
+            5,
+            5,
+            5,
+            5,
+            10,
+            8,
+            5,
+        )
+    }
+
+    /// Overrides both the sheet and the effective value of `attribute`.
+    pub fn set_attribute(character: &mut Character, attribute: Attribute, value: i32) {
+        character
+            .attributes
+            .insert(attribute, AttributeValue::new(value, value));
+    }
 
     fn populated_character() -> Character {
         let mut character = Character::new(
@@ -1293,6 +2014,127 @@ mod tests {
         assert_eq!(character.modifier_for_tag("sehen"), 0);
     }
 
+    #[test]
+    fn test_melee_specializations_continue_from_the_general_level() {
+        let mut character = unencumbered_shooter(); // REF 8
+        character.skills.push(Skill::new(
+            "Nahkampf allgemein".to_string(),
+            Attribute::Reflexes,
+            2,
+            1,
+        ));
+        assert_eq!(
+            character.raise_skill("Kurz"),
+            Err("'Kurz' can only be raised once 'Nahkampf allgemein' reaches level 3".to_string())
+        );
+        assert_eq!(character.raise_skill("Nahkampf allgemein"), Ok(30));
+        assert_eq!(
+            character.raise_skill("Nahkampf allgemein"),
+            Err(
+                "'Nahkampf allgemein' stops at level 3, raise one of Kurz, Mittel, Lang instead"
+                    .to_string()
+            )
+        );
+
+        // unbought specializations roll on the general level
+        assert_eq!(character.skill_level("Kurz").unwrap().1, 3);
+        assert_eq!(character.raise_skill("Kurz"), Ok(40));
+        assert_eq!(character.raise_skill("Kurz"), Ok(50));
+        assert_eq!(
+            character.check_values(&CheckSpec::Skill("Kurz".to_string())),
+            Ok((8, 5))
+        );
+        assert_eq!(
+            character.check_values(&CheckSpec::Skill("Mittel".to_string())),
+            Ok((8, 3))
+        );
+        let modifiers = character
+            .check_modifiers(&CheckSpec::Skill("Lang".to_string()))
+            .unwrap();
+        assert!(modifiers.contains(&CheckModifier::new(
+            ModifierSource::Skill("Nahkampf allgemein".to_string()),
+            3
+        )));
+        assert_eq!(character.raise_skill("Mittel"), Ok(40));
+        assert_eq!(character.skill_level("Kurz").unwrap().1, 5);
+        assert_eq!(character.skill_level("Mittel").unwrap().1, 4);
+        assert_eq!(character.skill_level("Schwimmen"), None);
+    }
+
+    #[test]
+    fn test_general_melee_and_dodge_stop_at_the_split_level() {
+        let mut character = unencumbered_shooter();
+        // an old sheet with the general skill above 3
+        character.skills.push(Skill::new(
+            "Nahkampf allgemein".to_string(),
+            Attribute::Reflexes,
+            5,
+            1,
+        ));
+        assert_eq!(character.skill_level(crate::DODGE_SKILL).unwrap().1, 3);
+        assert_eq!(character.skill_level("Lang").unwrap().1, 3);
+    }
+
+    #[test]
+    fn test_situation_bonuses_shifts_and_tags() {
+        use crate::advantages::{Advantage, AdvantageKind, ModifierTarget};
+        use crate::dice::{CheckModifier, ModifierSource, Situation};
+        let mut character = unencumbered_shooter(); // REF 8, Pistole 4
+        character.advantages.push(
+            Advantage::new(
+                "Gute Ohren".to_string(),
+                AdvantageKind::Advantage,
+                1,
+                "Lausch-Bonus".to_string(),
+            )
+            .with_modifier(ModifierTarget::Tag("hören".to_string()), 2),
+        );
+        let situation = Situation::new()
+            .with_bonus("prone", 1)
+            .with_bonus("tripod", 2)
+            .with_difficulty("unfamiliar weapon", 3)
+            .with_tag("hören")
+            .with_tag("sehen")
+            .with_tag("hören");
+
+        let mut roller = crate::dice::SequenceRoller::new(vec![2]);
+        let result = character
+            .check_skill_with("Pistole", 0, Difficulty::Normal, &situation, &mut roller)
+            .unwrap();
+        // 8 + 4 + 1 + 2 + 2 = 17 misses the auto-success vs 15 + 3
+        assert_eq!(result.target, 18);
+        assert_eq!(result.total, 19);
+        assert!(result.outcome.is_success());
+        assert_eq!(
+            result.modifiers[2..],
+            [
+                CheckModifier::new(ModifierSource::Situational("prone".to_string()), 1),
+                CheckModifier::new(ModifierSource::Situational("tripod".to_string()), 2),
+                CheckModifier::new(ModifierSource::Advantage("Gute Ohren".to_string()), 2),
+            ]
+        );
+        assert_eq!(
+            result.difficulty_shifts,
+            vec![CheckModifier::new(
+                ModifierSource::Situational("unfamiliar weapon".to_string()),
+                3
+            )]
+        );
+
+        // without the tag the advantage stays out of the roll
+        let mut roller = crate::dice::SequenceRoller::new(vec![2]);
+        let result = character
+            .check_attribute_with(
+                Attribute::Reflexes,
+                0,
+                Difficulty::Easy,
+                &Situation::new().with_bonus("head shot", -4),
+                &mut roller,
+            )
+            .unwrap();
+        assert_eq!(result.total, 8 - 4 + 2);
+    }
+
     #[test]
     fn test_ko_check_maluses_follow_wound_track() {
         let mut character = unencumbered_shooter(); // BODY 10
@@ -1323,6 +2165,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ko_check_pulls_in_tagged_advantages() {
+        use crate::advantages::{Advantage, AdvantageKind, ModifierTarget};
+        use crate::dice::{CheckModifier, ModifierSource, Situation};
+        let mut character = unencumbered_shooter(); // BODY 10
+        character.advantages.push(
+            Advantage::new(
+                "Zäh".to_string(),
+                AdvantageKind::Advantage,
+                2,
+                "Steckt Würfe weg".to_string(),
+            )
+            .with_modifier(ModifierTarget::Tag("geworfen".to_string()), 2),
+        );
+        // Mortal 2 (Stun -5): BODY 5 - 2 thrown + 2 tagged + die 4 = 9 vs 10
+        character.current_damage = 22;
+        let situation = Situation::new()
+            .with_bonus("thrown", -2)
+            .with_tag("geworfen");
+        let mut roller = crate::dice::SequenceRoller::new(vec![4]);
+        let result = character.ko_check_with(&situation, &mut roller);
+        assert_eq!(result.total, 9);
+        assert_eq!(
+            result.modifiers[2..],
+            [
+                CheckModifier::new(ModifierSource::Situational("thrown".to_string()), -2),
+                CheckModifier::new(ModifierSource::Advantage("Zäh".to_string()), 2),
+            ]
+        );
+    }
+
     #[test]
     fn test_real_damage_requires_ko_check() {
         let mut character = unencumbered_shooter();
@@ -1495,20 +2368,7 @@ mod tests {
     }
 
     fn unencumbered_shooter() -> Character {
-        let mut character = Character::new(
-            "Shooter".to_string(),
-            "Solo".to_string(),
-            25,
-            5,
-            6,
-            5,
-            5,
-            5,
-            5,
-            10,
-            8,
-            5,
-        );
+        let mut character = solo("Shooter");
         character
             .skills
             .push(Skill::new("Pistole".to_string(), Attribute::Reflexes, 4, 1));
@@ -1527,6 +2387,152 @@ mod tests {
         assert!(result.outcome.is_success());
     }
 
+    #[test]
+    fn test_checks_label_recorded_rolls() {
+        use crate::dice::RecordingRoller;
+        let mut character = unencumbered_shooter();
+        let mut roller = RecordingRoller::new(crate::dice::SequenceRoller::new(vec![3, 4]));
+        character
+            .check_skill("Pistole", 0, Difficulty::Hard, &mut roller)
+            .unwrap();
+        character
+            .check_attribute(Attribute::Tech, 0, Difficulty::Normal, &mut roller)
+            .unwrap();
+        let contexts: Vec<&str> = roller
+            .log()
+            .rolls
+            .iter()
+            .map(|roll| roll.context.as_str())
+            .collect();
+        assert_eq!(
+            contexts,
+            vec!["Shooter: Pistole vs 20", "Shooter: Tech vs 15"]
+        );
+    }
+
+    #[test]
+    fn test_luck_for_auto_success() {
+        let mut character = unencumbered_shooter(); // REF 8 + Pistole 4, 5 luck
+        assert_eq!(
+            character.luck_for_auto_success("Pistole", Difficulty::Easy),
+            Ok(0)
+        );
+        assert_eq!(
+            character.luck_for_auto_success("Pistole", Difficulty::Normal),
+            Ok(3)
+        );
+        assert_eq!(
+            character.luck_for_auto_success("Pistole", Difficulty::Hard),
+            Err(
+                "Character 'Shooter' needs 8 luck points for an auto-success on 'Pistole' vs 20, but has only 5"
+                    .to_string()
+            )
+        );
+
+        // the pending bruise malus and wounds raise the price
+        character.pending_roll_malus = 1;
+        character.current_damage = 6; // Serious: -2 REF
+        character.current_luck = 10;
+        assert_eq!(
+            character.luck_for_auto_success("Pistole", Difficulty::Normal),
+            Ok(3 + 1 + 2)
+        );
+    }
+
+    #[test]
+    fn test_luck_for_chance() {
+        // REF 8 + Pistole 4 vs 20
+        // no luck: die 8+ succeeds (0.3); 1 luck: 7+ (0.4); 2 luck: 6+ (0.5)
+        let mut character = unencumbered_shooter();
+        assert_eq!(
+            character.luck_for_chance("Pistole", Difficulty::Hard, 0.5),
+            Ok(2)
+        );
+        assert_eq!(
+            character.luck_for_chance("Pistole", Difficulty::Hard, 0.3),
+            Ok(0)
+        );
+        // 5 luck: die 3+ succeeds (0.8) — 0.9 is out of reach
+        assert_eq!(
+            character.luck_for_chance("Pistole", Difficulty::Hard, 0.9),
+            Err(
+                "Character 'Shooter' can't reach a 90% chance on 'Pistole' vs 20 with 5 luck points"
+                    .to_string()
+            )
+        );
+
+        // encumbrance costs REF and therefore luck
+        character.inventory.push(Box::new(Item::new(
+            None,
+            "Schrottkiste".to_string(),
+            1,
+            50_000,
+            0,
+            "heavy junk".to_string(),
+        )));
+        assert_eq!(
+            character.luck_for_chance("Pistole", Difficulty::Hard, 0.5),
+            Ok(3)
+        );
+    }
+
+    #[test]
+    fn test_skill_check_odds_include_pending_malus() {
+        let mut character = unencumbered_shooter();
+        character.pending_roll_malus = 2;
+        let odds = character
+            .skill_check_odds("Pistole", 0, Difficulty::Normal)
+            .unwrap();
+        // REF 8 + Pistole 4 - 2 vs 15: die 5+ succeeds
+        assert!((odds.success_chance() - 0.6).abs() < 1e-9);
+        assert_eq!(character.pending_roll_malus, 2, "odds don't consume it");
+    }
+
+    #[test]
+    fn test_declared_actions_malus_lasts_the_round() {
+        let mut character = unencumbered_shooter(); // REF 8 + Pistole 4
+        character.declare_actions(3, None).unwrap();
+        assert_eq!(character.action_malus(), 6);
+
+        // every check of the round carries -3 per extra action
+        let mut roller = crate::dice::SequenceRoller::new(vec![5, 5]);
+        let shot = character
+            .check_skill("Pistole", 0, Difficulty::Normal, &mut roller)
+            .unwrap();
+        assert_eq!(shot.total, 8 + 4 - 6 + 5);
+        let dive = character
+            .check_attribute(Attribute::Reflexes, 0, Difficulty::Easy, &mut roller)
+            .unwrap();
+        assert_eq!(dive.total, 8 - 6 + 5);
+
+        character.end_round();
+        assert_eq!(character.action_malus(), 0);
+        let mut roller = crate::dice::SequenceRoller::new(vec![5]);
+        let shot = character
+            .check_skill("Pistole", 0, Difficulty::Hard, &mut roller)
+            .unwrap();
+        assert_eq!(shot.total, 8 + 4 + 5);
+    }
+
+    #[test]
+    fn test_declared_actions_are_capped_by_shots_per_round() {
+        let mut character = unencumbered_shooter();
+        assert_eq!(
+            character.declare_actions(3, Some(2)),
+            Err(
+                "Character 'Shooter' can't take 3 actions with a weapon firing 2 shots per round"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            character.declare_actions(0, None),
+            Err("Character 'Shooter' must declare at least one action, got 0".to_string())
+        );
+        assert_eq!(character.action_malus(), 0);
+        character.declare_actions(2, Some(2)).unwrap();
+        assert_eq!(character.action_malus(), 3);
+    }
+
     #[test]
     fn test_check_skill_unknown_skill_errors() {
         let mut character = unencumbered_shooter();
@@ -1574,9 +2580,7 @@ mod tests {
     fn test_start_session_regenerates_half_current_base_luck() {
         let mut character = unencumbered_shooter();
         // current base LUCK 9 to mirror the example from the table rules
-        character
-            .attributes
-            .insert(Attribute::Luck, AttributeValue::new(9, 9));
+        set_attribute(&mut character, Attribute::Luck, 9);
         character.current_luck = 1;
         character.start_session();
         // 1 + ceil(9/2) = 6
@@ -1592,9 +2596,7 @@ mod tests {
     fn test_sacrifice_luck_lowers_current_base_and_regen() {
         let mut character = unencumbered_shooter();
         // starting base 9, still at full current base and pool
-        character
-            .attributes
-            .insert(Attribute::Luck, AttributeValue::new(9, 9));
+        set_attribute(&mut character, Attribute::Luck, 9);
         character.current_luck = 9;
 
         character.sacrifice_luck(4).unwrap();
@@ -1644,6 +2646,80 @@ mod tests {
         assert!(!result.outcome.is_success());
     }
 
+    #[test]
+    fn test_check_result_itemizes_every_modifier() {
+        use crate::advantages::{Advantage, AdvantageKind, ModifierTarget};
+        use crate::dice::{CheckModifier, ModifierSource};
+        let mut character = unencumbered_shooter(); // REF 8, Pistole 4
+        character.current_damage = 6; // Serious: -2 REF
+        character.pending_roll_malus = 2;
+        character.inventory.push(Box::new(Item::new(
+            None,
+            "Schrottkiste".to_string(),
+            1,
+            50_000,
+            0,
+            "heavy junk".to_string(),
+        )));
+        character.advantages.push(
+            Advantage::new(
+                "Waffennarr".to_string(),
+                AdvantageKind::Advantage,
+                10,
+                "+3 auf Pistole".to_string(),
+            )
+            .with_modifier(ModifierTarget::Skill("Pistole".to_string()), 3),
+        );
+
+        let mut roller = crate::dice::SequenceRoller::new(vec![3]);
+        let result = character
+            .check_skill("Pistole", 1, Difficulty::Normal, &mut roller)
+            .unwrap();
+        assert_eq!(
+            result.modifiers,
+            vec![
+                CheckModifier::new(
+                    ModifierSource::Attribute(Attribute::Reflexes.to_string()),
+                    8
+                ),
+                CheckModifier::new(
+                    ModifierSource::WoundState(WoundState::Serious.to_string()),
+                    -2
+                ),
+                CheckModifier::new(ModifierSource::Encumbrance, -1),
+                CheckModifier::new(ModifierSource::Skill("Pistole".to_string()), 4),
+                CheckModifier::new(ModifierSource::Advantage("Waffennarr".to_string()), 3),
+                CheckModifier::new(ModifierSource::PendingRollMalus, -2),
+                CheckModifier::new(ModifierSource::Luck, 1),
+            ]
+        );
+        // 8 - 2 - 1 + 4 + 3 - 2 + 1 = 11, plus the natural 3
+        assert_eq!(result.modifier_total(), 11);
+        assert_eq!(result.total, 14);
+        assert_eq!(result.modifiers[1].to_string(), "-2 wounds (Serious)");
+    }
+
+    #[test]
+    fn test_attribute_breakdown_matches_effective_attribute() {
+        use crate::dice::ModifierSource;
+        let mut character = unencumbered_shooter();
+        character.current_damage = 14; // Mortal 0: REF 8 -> 3
+        character.inventory.push(Box::new(Item::new(
+            None,
+            "Amboss".to_string(),
+            1,
+            100_000,
+            0,
+            "full capacity".to_string(),
+        ))); // encumbrance -4
+        let breakdown = character.attribute_breakdown(Attribute::Reflexes);
+        let values: Vec<i32> = breakdown.iter().map(|modifier| modifier.value).collect();
+        // 8 - 5 - 4 = -1, floored back to 0
+        assert_eq!(values, vec![8, -5, -4, 1]);
+        assert_eq!(breakdown[3].source, ModifierSource::AttributeFloor);
+        assert_eq!(character.effective_attribute(Attribute::Reflexes), 0);
+    }
+
     #[test]
     fn test_check_attribute_auto_success() {
         let mut character = unencumbered_shooter();
@@ -0,0 +1,1072 @@
+use crate::armor::HitZone;
+use crate::character::{Character, CheckSpec, HitOutcome};
+use crate::dice::{CheckModifier, CheckResult, DieRoller, Difficulty, ModifierSource, Situation};
+use crate::hit_location::{called_shot_modifier, HitLocationTable};
+use crate::weapons::{DamageType, Malfunction, Weapon};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// CP2020 range brackets, relative to the weapon's range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RangeBracket {
+    /// Up to 1 m.
+    PointBlank,
+    /// Up to a quarter of the weapon's range.
+    Close,
+    /// Up to half the weapon's range.
+    Medium,
+    /// Up to the weapon's range.
+    Long,
+    /// Up to twice the weapon's range.
+    Extreme,
+}
+
+impl RangeBracket {
+    /// The bracket a target at `distance_m` falls into, or `None` beyond
+    /// twice the weapon range.
+    pub fn for_distance(distance_m: i32, weapon_range_m: i32) -> Option<Self> {
+        if distance_m <= 1 {
+            Some(RangeBracket::PointBlank)
+        } else if distance_m * 4 <= weapon_range_m {
+            Some(RangeBracket::Close)
+        } else if distance_m * 2 <= weapon_range_m {
+            Some(RangeBracket::Medium)
+        } else if distance_m <= weapon_range_m {
+            Some(RangeBracket::Long)
+        } else if distance_m <= 2 * weapon_range_m {
+            Some(RangeBracket::Extreme)
+        } else {
+            None
+        }
+    }
+
+    /// To-hit difficulty: 10, 15, 20, 25, 30.
+    pub fn difficulty(self) -> Difficulty {
+        match self {
+            RangeBracket::PointBlank => Difficulty::Easy,
+            RangeBracket::Close => Difficulty::Normal,
+            RangeBracket::Medium => Difficulty::Hard,
+            RangeBracket::Long => Difficulty::Custom(25),
+            RangeBracket::Extreme => Difficulty::Custom(30),
+        }
+    }
+}
+
+/// Aids that steady a shot (Regeln → Zielhilfen).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AimingAid {
+    /// Lying prone or resting the weapon: +1.
+    Rest,
+    /// Tripod or turret: +2.
+    Tripod,
+    /// Laser pointer: +1.
+    Laser,
+    /// Scope: +1, +2 at extreme range.
+    Scope,
+}
+
+impl AimingAid {
+    pub fn bonus(self, range: RangeBracket) -> i32 {
+        match self {
+            AimingAid::Rest | AimingAid::Laser => 1,
+            AimingAid::Tripod => 2,
+            AimingAid::Scope if range == RangeBracket::Extreme => 2,
+            AimingAid::Scope => 1,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            AimingAid::Rest => "prone/rest",
+            AimingAid::Tripod => "tripod/turret",
+            AimingAid::Laser => "laser",
+            AimingAid::Scope => "scope",
+        }
+    }
+}
+
+/// A shooter's preparation for the next shot (Regeln → Zielen, Hinterhalt).
+///
+/// Aiming builds up +1 per round spent aiming, at most +3, and is used up by
+/// the next shot. Whether the target holds still enough to aim at is the
+/// GM's call: only call [`AimingState::aim`] if it does. An ambush gives +5
+/// for one round only — [`Character::end_round`] ends it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AimingState {
+    pub rounds_aimed: i32,
+    pub ambush: bool,
+}
+
+impl AimingState {
+    pub const MAX_AIM_BONUS: i32 = 3;
+    pub const AMBUSH_BONUS: i32 = 5;
+
+    /// Spends a round aiming.
+    pub fn aim(&mut self) {
+        self.rounds_aimed = (self.rounds_aimed + 1).min(Self::MAX_AIM_BONUS);
+    }
+
+    /// Strikes from an ambush this round.
+    pub fn ambush(&mut self) {
+        self.ambush = true;
+    }
+
+    pub fn aim_bonus(&self) -> i32 {
+        self.rounds_aimed.min(Self::MAX_AIM_BONUS)
+    }
+
+    /// The shot is fired: the aiming is used up (the ambush lasts the round).
+    pub fn shot_fired(&mut self) {
+        self.rounds_aimed = 0;
+    }
+
+    pub fn end_round(&mut self) {
+        self.ambush = false;
+    }
+}
+
+/// How the weapon is fired (Regeln → Autofeuer, Kurzfeuerstoß).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FireMode {
+    /// One shot.
+    Single,
+    /// Three rounds at a single target: +3, 1d2 of them hit.
+    ThreeRoundBurst,
+    /// Spraying `bullets` rounds (at most the weapon's rate of fire):
+    /// +1 per 10 bullets when close, −1 per 10 otherwise; one bullet hits
+    /// per point over the target. No precision boni.
+    Autofire { bullets: i32 },
+}
+
+impl FireMode {
+    pub const BURST_BONUS: i32 = 3;
+
+    /// Rounds this mode fires.
+    pub fn rounds(self) -> i32 {
+        match self {
+            FireMode::Single => 1,
+            FireMode::ThreeRoundBurst => 3,
+            FireMode::Autofire { bullets } => bullets,
+        }
+    }
+
+    /// The to-hit modifier of this mode at `range`, if any.
+    pub fn modifier(self, range: RangeBracket) -> Option<CheckModifier> {
+        let (label, value) = match self {
+            FireMode::Single => return None,
+            FireMode::ThreeRoundBurst => ("3-round burst", Self::BURST_BONUS),
+            FireMode::Autofire { bullets } => match range {
+                RangeBracket::PointBlank | RangeBracket::Close => ("autofire", bullets / 10),
+                _ => ("autofire", -(bullets / 10)),
+            },
+        };
+        (value != 0)
+            .then(|| CheckModifier::new(ModifierSource::Situational(label.to_string()), value))
+    }
+
+    /// Aiming and aiming aids count (everything but autofire).
+    pub fn allows_precision(self) -> bool {
+        !matches!(self, FireMode::Autofire { .. })
+    }
+}
+
+/// Motionless target (Regeln → Abwarten).
+pub const MOTIONLESS_TARGET_BONUS: i32 = 4;
+/// Difficulty shift for a weapon the character isn't familiar with, within
+/// a class they are trained in (Q25).
+pub const UNFAMILIAR_WEAPON_SHIFT: i32 = 3;
+/// Checks with an unfamiliar weapon until it becomes familiar (Q35).
+pub const WEAPON_FAMILIARITY_USES: i32 = 10;
+
+/// What the shooter declares for a ranged attack.
+///
+/// Built with [`RangedAttack::new`] from the weapon (by the UUID of the item
+/// in the shooter's inventory) and the distance to the target, which gives
+/// the range bracket and with it the difficulty. Situation, aiming aids,
+/// called shot, hit location table and committed luck are set with the
+/// `with_*` methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangedAttack {
+    pub weapon: Uuid,
+    pub distance_m: i32,
+    pub situation: Situation,
+    pub aids: Vec<AimingAid>,
+    pub called_shot: Option<HitZone>,
+    /// The table random hits are located on, CP2020 by default.
+    pub hit_locations: HitLocationTable,
+    /// The target reliably doesn't move (asleep, unconscious, …).
+    pub motionless_target: bool,
+    pub fire_mode: FireMode,
+    pub luck: i32,
+}
+
+impl RangedAttack {
+    pub fn new(weapon: Uuid, distance_m: i32) -> Self {
+        RangedAttack {
+            weapon,
+            distance_m,
+            situation: Situation::new(),
+            aids: Vec::new(),
+            called_shot: None,
+            hit_locations: HitLocationTable::default(),
+            motionless_target: false,
+            fire_mode: FireMode::Single,
+            luck: 0,
+        }
+    }
+
+    pub fn with_situation(mut self, situation: Situation) -> Self {
+        self.situation = situation;
+        self
+    }
+
+    pub fn with_aid(mut self, aid: AimingAid) -> Self {
+        self.aids.push(aid);
+        self
+    }
+
+    /// Aims at `zone` for [`CALLED_SHOT_MALUS`](crate::CALLED_SHOT_MALUS);
+    /// every hit lands there.
+    pub fn with_called_shot(mut self, zone: HitZone) -> Self {
+        self.called_shot = Some(zone);
+        self
+    }
+
+    pub fn with_hit_locations(mut self, table: HitLocationTable) -> Self {
+        self.hit_locations = table;
+        self
+    }
+
+    pub fn with_motionless_target(mut self) -> Self {
+        self.motionless_target = true;
+        self
+    }
+
+    pub fn with_fire_mode(mut self, fire_mode: FireMode) -> Self {
+        self.fire_mode = fire_mode;
+        self
+    }
+
+    pub fn with_luck(mut self, luck: i32) -> Self {
+        self.luck = luck;
+        self
+    }
+
+    /// The to-hit modifiers of this attack at `range` from the shooter's
+    /// aiming state: aiming, ambush, aids, motionless target and called shot.
+    /// Autofire skips the precision boni (aiming and aids).
+    pub fn aiming_modifiers(
+        &self,
+        aiming: &AimingState,
+        range: RangeBracket,
+    ) -> Vec<CheckModifier> {
+        let mut modifiers = Vec::new();
+        let mut push = |label: &str, value: i32| {
+            if value != 0 {
+                modifiers.push(CheckModifier::new(
+                    ModifierSource::Situational(label.to_string()),
+                    value,
+                ));
+            }
+        };
+        let precision = self.fire_mode.allows_precision();
+        if precision {
+            push("aiming", aiming.aim_bonus());
+        }
+        if aiming.ambush {
+            push("ambush", AimingState::AMBUSH_BONUS);
+        }
+        for aid in self.aids.iter().filter(|_| precision) {
+            push(aid.label(), aid.bonus(range));
+        }
+        if self.motionless_target {
+            push("motionless target", MOTIONLESS_TARGET_BONUS);
+        }
+        if let Some(zone) = self.called_shot {
+            modifiers.push(called_shot_modifier(zone));
+        }
+        modifiers
+    }
+}
+
+/// One projectile that hit the target.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BulletHit {
+    pub zone: HitZone,
+    /// Damage rolled on the weapon's dice, before armor.
+    pub damage: i32,
+    /// Of the round that hit (the weapon's own for weapons without ammo).
+    pub damage_type: DamageType,
+    pub outcome: HitOutcome,
+}
+
+/// Everything a ranged attack did.
+#[derive(Debug, PartialEq, Eq)]
+pub struct AttackReport {
+    pub range: RangeBracket,
+    /// The to-hit check on the weapon skill.
+    pub check: CheckResult,
+    pub fire_mode: FireMode,
+    /// One entry per projectile that hit, in the order they hit; empty on
+    /// a miss.
+    pub hits: Vec<BulletHit>,
+    /// Rounds fired from the magazine (0 for weapons without one), hit or
+    /// miss.
+    pub ammo_spent: i32,
+    /// At least one hit did real damage: the target must make a KO check,
+    /// see [`Character::ko_check`].
+    pub ko_check_required: bool,
+    /// The malfunction a fumbled shot caused, see [`malfunction_check`].
+    pub malfunction: Option<Malfunction>,
+}
+
+/// Resolves a ranged attack from to-hit roll to damage on the target.
+///
+/// The distance gives the range bracket and difficulty. The to-hit check is
+/// rolled on the weapon's skill with the weapon accuracy, the fire mode and
+/// aiming modifiers and the declared situation; the shot uses up the
+/// shooter's aiming, and a weapon the shooter isn't familiar with raises
+/// the difficulty by [`UNFAMILIAR_WEAPON_SHIFT`]. On a success the number of
+/// hits follows from the fire mode; every bullet gets its own hit location
+/// (on the attack's table, or the called zone) and damage roll and goes
+/// through [`Character::hit`] — as a gunshot if the weapon is a firearm.
+///
+/// Autofire lands one bullet per point over the target, but at least one:
+/// meeting the target exactly is still a success. A fumbled shot with a
+/// firearm rolls a [`malfunction_check`]; a malfunction stays on the weapon
+/// until [`Character::clear_malfunction`] clears it.
+pub fn ranged_attack(
+    shooter: &mut Character,
+    target: &mut Character,
+    attack: &RangedAttack,
+    roller: &mut dyn DieRoller,
+) -> Result<AttackReport, String> {
+    let weapon = shooter.weapon(attack.weapon)?.clone();
+    if weapon.category.is_melee() {
+        return Err(format!(
+            "Weapon '{}' is a melee weapon, not a ranged one",
+            weapon.item.name
+        ));
+    }
+    weapon.ready()?;
+    check_fire_mode(&weapon, attack)?;
+    let range = RangeBracket::for_distance(attack.distance_m, weapon.range_m).ok_or_else(|| {
+        format!(
+            "A target at {} m is out of range for '{}' ({} m)",
+            attack.distance_m, weapon.item.name, weapon.range_m
+        )
+    })?;
+
+    shooter.check_values(&CheckSpec::Skill(weapon.skill.clone()))?;
+    shooter.check_luck(attack.luck)?;
+    // the rounds leave the barrel whether they hit or not
+    let fired = if weapon.magazine_size > 0 {
+        shooter
+            .weapon_mut(attack.weapon)?
+            .fire(attack.fire_mode.rounds())?
+    } else {
+        Vec::new()
+    };
+
+    let mut situation = attack.situation.clone();
+    if weapon.accuracy != 0 {
+        situation = situation.with_modifier(CheckModifier::new(
+            ModifierSource::WeaponAccuracy(weapon.item.name.clone()),
+            weapon.accuracy,
+        ));
+    }
+    if let Some(modifier) = attack.fire_mode.modifier(range) {
+        situation = situation.with_modifier(modifier);
+    }
+    for modifier in attack.aiming_modifiers(&shooter.aiming, range) {
+        situation = situation.with_modifier(modifier);
+    }
+    if let Some(shift) = shooter.unfamiliar_weapon_shift(&weapon) {
+        situation = situation.with_difficulty_shift(shift);
+    }
+    let check = shooter.check_skill_with(
+        &weapon.skill,
+        attack.luck,
+        range.difficulty(),
+        &situation,
+        roller,
+    )?;
+    shooter.aiming.shot_fired();
+    shooter.practice_weapon(&weapon);
+    let malfunction = if weapon.category.is_firearm() {
+        malfunction_check(&weapon, &check, roller)
+    } else {
+        None
+    };
+    if malfunction.is_some() {
+        shooter.weapon_mut(attack.weapon)?.malfunction = malfunction;
+    }
+
+    let mut hits = Vec::new();
+    if check.outcome.is_success() {
+        let bullets = match attack.fire_mode {
+            FireMode::Single => 1,
+            FireMode::ThreeRoundBurst => {
+                roller.annotate(&format!("{}: burst hits", weapon.item.name));
+                roller.roll(2)
+            }
+            FireMode::Autofire { bullets } => (check.total - check.target).clamp(1, bullets),
+        };
+        for bullet in 0..bullets as usize {
+            let zone = attack
+                .hit_locations
+                .locate(attack.called_shot, &target.name, roller);
+            let damage_type = fired.get(bullet).copied().unwrap_or(weapon.damage_type);
+            hits.push(shoot(&weapon, zone, damage_type, target, roller));
+        }
+    }
+    Ok(AttackReport {
+        range,
+        check,
+        fire_mode: attack.fire_mode,
+        ko_check_required: hits.iter().any(|hit| hit.outcome.ko_check_required),
+        hits,
+        ammo_spent: fired.len() as i32,
+        malfunction,
+    })
+}
+
+/// Rolls whether a fumbled shot made the weapon malfunction: a d10 up to
+/// the [`Reliability`](crate::Reliability) threshold (3 very reliable,
+/// 5 standard, 8 unreliable) does. Every natural 1 is a fumble, confirmed
+/// or not (Q34): a critical failure (confirmation 1) jams the weapon, any
+/// other confirmation is a misfire. No roll without a fumble.
+pub fn malfunction_check(
+    weapon: &Weapon,
+    check: &CheckResult,
+    roller: &mut dyn DieRoller,
+) -> Option<Malfunction> {
+    let malfunction = match check.fumble_confirmation? {
+        1 => Malfunction::Jammed,
+        _ => Malfunction::Misfire,
+    };
+    roller.annotate(&format!("{}: reliability", weapon.item.name));
+    if roller.d10() <= weapon.reliability.malfunction_threshold() {
+        Some(malfunction)
+    } else {
+        None
+    }
+}
+
+fn check_fire_mode(weapon: &Weapon, attack: &RangedAttack) -> Result<(), String> {
+    let rounds = attack.fire_mode.rounds();
+    match attack.fire_mode {
+        FireMode::Single => return Ok(()),
+        FireMode::Autofire { .. } if attack.called_shot.is_some() => {
+            return Err("Autofire can't be a called shot".to_string())
+        }
+        _ => {}
+    }
+    if weapon.magazine_size == 0 || rounds < 1 || rounds > weapon.rate_of_fire {
+        return Err(format!(
+            "Weapon '{}' (ROF {}) can't fire {} rounds at once",
+            weapon.item.name, weapon.rate_of_fire, rounds
+        ));
+    }
+    Ok(())
+}
+
+/// Rolls the damage of one projectile and applies it to `target`.
+fn shoot(
+    weapon: &Weapon,
+    zone: HitZone,
+    damage_type: DamageType,
+    target: &mut Character,
+    roller: &mut dyn DieRoller,
+) -> BulletHit {
+    roller.annotate(&format!("{}: damage", weapon.item.name));
+    let damage = weapon.damage.roll(roller).total;
+    let outcome = target.hit(
+        damage,
+        zone,
+        damage_type,
+        weapon.category.is_firearm(),
+        roller,
+    );
+    BulletHit {
+        zone,
+        damage,
+        damage_type,
+        outcome,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::tests::solo;
+    use crate::character::{Attribute, Skill};
+    use crate::dice::{Outcome, SequenceRoller};
+    use crate::weapons::{Ammunition, WeaponCatalog};
+
+    fn character(name: &str) -> Character {
+        let mut character = solo(name);
+        character
+            .skills
+            .push(Skill::new("Pistole".to_string(), Attribute::Reflexes, 4, 1));
+        character
+    }
+
+    fn ammunition(caliber: &str, damage_type: DamageType, count: i32) -> Ammunition {
+        Ammunition::new(
+            format!("{} {}", caliber, damage_type),
+            caliber.to_string(),
+            damage_type,
+            count,
+            10,
+            1,
+        )
+    }
+
+    /// A shooter carrying `weapon`, familiar with it and fully loaded if it
+    /// takes ammunition.
+    fn armed_shooter(weapon: &str) -> (Character, Uuid) {
+        let mut shooter = character("Shooter");
+        shooter.learn_weapon(weapon);
+        let weapon = WeaponCatalog::builtin().weapon(weapon).unwrap();
+        let uuid = weapon.item.uuid;
+        let caliber = weapon.caliber.clone();
+        shooter.inventory.push(Box::new(weapon));
+        if let Some(caliber) = caliber {
+            let rounds = ammunition(&caliber, DamageType::Blunt, 100);
+            let rounds_uuid = rounds.item.uuid;
+            shooter.inventory.push(Box::new(rounds));
+            shooter.reload(uuid, rounds_uuid, None).unwrap();
+        }
+        (shooter, uuid)
+    }
+
+    #[test]
+    fn test_hit_rolls_location_and_damage_into_target() {
+        let (mut shooter, grach) = armed_shooter("Grach");
+        let mut target = character("Target");
+        // to-hit 5: 8 + 4 + 5 = 17 vs 15; location 4 = stomach;
+        // damage 2d6+1 = 3 + 3 + 1 = 7 > 4: penetration cap d10 = 9 -> 13, no cap
+        let mut roller = SequenceRoller::new(vec![5, 4, 3, 3, 9]);
+        let report = ranged_attack(
+            &mut shooter,
+            &mut target,
+            &RangedAttack::new(grach, 10),
+            &mut roller,
+        )
+        .unwrap();
+        assert!(report.check.outcome.is_success());
+        assert_eq!(report.ammo_spent, 1);
+        assert_eq!(report.hits.len(), 1);
+        assert_eq!(report.hits[0].zone, HitZone::Stomach);
+        assert_eq!(report.hits[0].damage, 7);
+        // BTM 4: 3 real damage, 4 Prellschaden
+        assert_eq!(report.hits[0].outcome.real_damage, 3);
+        assert!(report.ko_check_required);
+        assert_eq!(target.current_damage, 3);
+    }
+
+    #[test]
+    fn test_hits_land_on_the_attack_hit_location_table() {
+        let (mut shooter, grach) = armed_shooter("Grach");
+        let mut target = character("Target");
+        // to-hit 5: 17 vs 15; d100 49 = right hand; damage 1 + 1 + 1 = 3
+        let mut roller = SequenceRoller::new(vec![5, 49, 1, 1]);
+        let attack = RangedAttack::new(grach, 10).with_hit_locations(HitLocationTable::detailed());
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap();
+        assert_eq!(report.hits[0].zone, HitZone::RightHand);
+        assert_eq!(report.hits[0].damage, 3);
+    }
+
+    #[test]
+    fn test_miss_spends_ammo_but_hits_nothing() {
+        let (mut shooter, grach) = armed_shooter("Grach");
+        let mut target = character("Target");
+        let mut roller = SequenceRoller::new(vec![2]);
+        let report = ranged_attack(
+            &mut shooter,
+            &mut target,
+            &RangedAttack::new(grach, 10),
+            &mut roller,
+        )
+        .unwrap();
+        assert!(!report.check.outcome.is_success());
+        assert!(report.hits.is_empty());
+        assert_eq!(report.ammo_spent, 1);
+        assert!(!report.ko_check_required);
+        assert_eq!(target.current_damage, 0);
+    }
+
+    #[test]
+    fn test_critical_fumble_jams_until_a_tech_check_clears_it() {
+        let (mut shooter, grach) = armed_shooter("Grach");
+        let mut target = character("Target");
+        let attack = RangedAttack::new(grach, 10);
+        // die 1, confirmation 1: critical failure; reliability 5 <= 5 (standard)
+        let mut roller = SequenceRoller::new(vec![1, 1, 5]);
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap();
+        assert_eq!(report.malfunction, Some(Malfunction::Jammed));
+        assert_eq!(
+            ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap_err(),
+            "Weapon 'Grach' can't fire: Jammed"
+        );
+
+        // TECH 5: die 3 -> 8 vs 15 fails, 10 + 5 -> 20 succeeds
+        let mut roller = SequenceRoller::new(vec![3, 10, 5]);
+        let check = shooter.clear_malfunction(grach, 0, &mut roller).unwrap();
+        assert!(!check.unwrap().outcome.is_success());
+        assert_eq!(
+            shooter.weapon(grach).unwrap().malfunction,
+            Some(Malfunction::Jammed)
+        );
+        let check = shooter.clear_malfunction(grach, 0, &mut roller).unwrap();
+        assert!(check.unwrap().outcome.is_success());
+        assert_eq!(shooter.weapon(grach).unwrap().malfunction, None);
+    }
+
+    #[test]
+    fn test_reliability_decides_whether_a_fumble_malfunctions() {
+        let mut target = character("Target");
+        // die 1, confirmation 3: embarrassing failure; reliability die 5
+        let fumble = || SequenceRoller::new(vec![1, 3, 5]);
+
+        // standard: 5 <= 5 misfires, clearing only takes the action
+        let (mut shooter, grach) = armed_shooter("Grach");
+        let attack = RangedAttack::new(grach, 10);
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut fumble()).unwrap();
+        assert_eq!(report.malfunction, Some(Malfunction::Misfire));
+        let mut roller = SequenceRoller::new(vec![]);
+        assert_eq!(shooter.clear_malfunction(grach, 0, &mut roller), Ok(None));
+        assert_eq!(
+            shooter.clear_malfunction(grach, 0, &mut roller),
+            Err("Weapon 'Grach' has no malfunction".to_string())
+        );
+
+        // very reliable: 5 > 3, the weapon keeps working
+        let (mut shooter, ak) = armed_shooter("AK-47");
+        shooter
+            .skills
+            .push(Skill::new("Gewehr".to_string(), Attribute::Reflexes, 4, 1));
+        let attack = RangedAttack::new(ak, 50);
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut fumble()).unwrap();
+        assert_eq!(report.malfunction, None);
+        assert_eq!(shooter.weapon(ak).unwrap().malfunction, None);
+    }
+
+    #[test]
+    fn test_unconfirmed_fumble_can_still_misfire() {
+        let (mut shooter, grach) = armed_shooter("Grach");
+        let mut target = character("Target");
+        // die 1, confirmation 8: a plain failure, but still a fumble;
+        // reliability 4 <= 5 misfires
+        let mut roller = SequenceRoller::new(vec![1, 8, 4]);
+        let attack = RangedAttack::new(grach, 10);
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap();
+        assert_eq!(report.check.outcome, Outcome::Failure);
+        assert_eq!(report.malfunction, Some(Malfunction::Misfire));
+    }
+
+    #[test]
+    fn test_malfunction_blocks_a_firearm_without_magazine() {
+        let mut shooter = character("Shooter");
+        let mut grach = WeaponCatalog::builtin().weapon("Grach").unwrap();
+        grach.magazine_size = 0;
+        grach.caliber = None;
+        grach.malfunction = Some(Malfunction::Jammed);
+        let grach_uuid = grach.item.uuid;
+        shooter.inventory.push(Box::new(grach));
+        let mut target = character("Target");
+        let mut roller = SequenceRoller::new(vec![]);
+        assert_eq!(
+            ranged_attack(
+                &mut shooter,
+                &mut target,
+                &RangedAttack::new(grach_uuid, 10),
+                &mut roller
+            )
+            .unwrap_err(),
+            "Weapon 'Grach' can't fire: Jammed"
+        );
+    }
+
+    #[test]
+    fn test_situation_and_weapon_accuracy_count_on_the_roll() {
+        let (mut shooter, uzi) = smg_shooter();
+        let mut target = character("Target");
+        // 8 + 2 + WA 1 + prone 1 = 12, die 2 -> 14 vs 15: miss
+        let mut roller = SequenceRoller::new(vec![2]);
+        let attack =
+            RangedAttack::new(uzi, 30).with_situation(Situation::new().with_bonus("prone", 1));
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap();
+        assert_eq!(report.check.total, 14);
+        assert!(report.check.modifiers.contains(&CheckModifier::new(
+            ModifierSource::WeaponAccuracy("Uzi Miniauto 9".to_string()),
+            1
+        )));
+    }
+
+    #[test]
+    fn test_range_brackets_follow_weapon_range() {
+        // Grach: 100 m
+        assert_eq!(
+            RangeBracket::for_distance(1, 100),
+            Some(RangeBracket::PointBlank)
+        );
+        assert_eq!(
+            RangeBracket::for_distance(25, 100),
+            Some(RangeBracket::Close)
+        );
+        assert_eq!(
+            RangeBracket::for_distance(26, 100),
+            Some(RangeBracket::Medium)
+        );
+        assert_eq!(
+            RangeBracket::for_distance(100, 100),
+            Some(RangeBracket::Long)
+        );
+        assert_eq!(
+            RangeBracket::for_distance(200, 100),
+            Some(RangeBracket::Extreme)
+        );
+        assert_eq!(RangeBracket::for_distance(201, 100), None);
+        assert_eq!(RangeBracket::Long.difficulty().target(), 25);
+    }
+
+    #[test]
+    fn test_aiming_builds_up_to_three_and_ambush_lasts_one_round() {
+        let mut aiming = AimingState::default();
+        for _ in 0..5 {
+            aiming.aim();
+        }
+        assert_eq!(aiming.aim_bonus(), 3);
+        aiming.ambush();
+        let attack = RangedAttack::new(Uuid::new_v4(), 10)
+            .with_aid(AimingAid::Tripod)
+            .with_aid(AimingAid::Scope);
+        let values: Vec<i32> = attack
+            .aiming_modifiers(&aiming, RangeBracket::Extreme)
+            .iter()
+            .map(|modifier| modifier.value)
+            .collect();
+        // aiming 3, ambush 5, tripod 2, scope at extreme range 2
+        assert_eq!(values, vec![3, 5, 2, 2]);
+
+        aiming.shot_fired();
+        assert_eq!(aiming.aim_bonus(), 0);
+        assert!(aiming.ambush, "the ambush lasts the whole round");
+        aiming.end_round();
+        assert!(!aiming.ambush);
+    }
+
+    #[test]
+    fn test_aimed_head_shot_uses_up_the_aiming() {
+        let (mut shooter, grach) = armed_shooter("Grach");
+        let mut target = character("Target");
+        shooter.aiming.aim();
+        shooter.aiming.aim();
+        // 8 + 4 + aiming 2 - called shot 4 = 10, die 6 -> 16 vs 15 (close);
+        // no location roll; damage 1 + 1 + 1 = 3
+        let mut roller = SequenceRoller::new(vec![6, 1, 1]);
+        let attack = RangedAttack::new(grach, 20).with_called_shot(HitZone::Head);
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap();
+        assert_eq!(report.range, RangeBracket::Close);
+        assert_eq!(report.check.total, 16);
+        assert_eq!(report.hits[0].zone, HitZone::Head);
+        assert_eq!(shooter.aiming.rounds_aimed, 0);
+    }
+
+    fn smg_shooter() -> (Character, Uuid) {
+        let (mut shooter, uzi) = armed_shooter("Uzi Miniauto 9");
+        shooter.skills.push(Skill::new(
+            "Maschinenpistole".to_string(),
+            Attribute::Reflexes,
+            2,
+            1,
+        ));
+        (shooter, uzi)
+    }
+
+    #[test]
+    fn test_three_round_burst_hits_one_or_two_bullets_separately() {
+        let (mut shooter, uzi) = smg_shooter();
+        let mut target = character("Target");
+        // 8 + 2 + WA 1 + burst 3 = 14, die 2 -> 16 vs 15 (close);
+        // 1d2 = 2 hits: chest for 1 + 1 + 1, right leg for 2 + 2 + 1 (cap d10 10)
+        let mut roller = SequenceRoller::new(vec![2, 2, 2, 1, 1, 7, 2, 2, 10]);
+        let attack = RangedAttack::new(uzi, 30).with_fire_mode(FireMode::ThreeRoundBurst);
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap();
+        assert_eq!(report.check.total, 16);
+        assert_eq!(report.ammo_spent, 3);
+        let hits: Vec<(HitZone, i32)> = report
+            .hits
+            .iter()
+            .map(|hit| (hit.zone, hit.damage))
+            .collect();
+        assert_eq!(hits, vec![(HitZone::Chest, 3), (HitZone::RightLeg, 5)]);
+    }
+
+    #[test]
+    fn test_autofire_hits_per_point_over_target_without_precision_boni() {
+        let (mut shooter, uzi) = smg_shooter();
+        shooter.aiming.aim();
+        let mut target = character("Target");
+        // 8 + 2 + WA 1 + 20 bullets close 2 = 13 (aiming ignored), die 3
+        // -> 16 vs 15: one bullet, right arm for 1 + 1 + 1
+        let mut roller = SequenceRoller::new(vec![3, 5, 1, 1]);
+        let attack = RangedAttack::new(uzi, 30)
+            .with_fire_mode(FireMode::Autofire { bullets: 20 })
+            .with_aid(AimingAid::Laser);
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap();
+        assert_eq!(report.check.total, 16);
+        assert_eq!(report.ammo_spent, 20);
+        assert_eq!(report.hits.len(), 1);
+        assert_eq!(report.hits[0].zone, HitZone::RightArm);
+        assert!(report.check.modifiers.iter().all(|modifier| modifier.source
+            != ModifierSource::Situational("aiming".to_string())
+            && modifier.source != ModifierSource::Situational("laser".to_string())));
+
+        // far away the bullets cost accuracy instead
+        let autofire = FireMode::Autofire { bullets: 25 };
+        assert_eq!(autofire.modifier(RangeBracket::Long).unwrap().value, -2);
+        assert_eq!(
+            autofire.modifier(RangeBracket::PointBlank).unwrap().value,
+            2
+        );
+    }
+
+    #[test]
+    fn test_fire_modes_need_a_capable_weapon() {
+        let (mut shooter, grach) = armed_shooter("Grach");
+        let mut target = character("Target");
+        let mut roller = SequenceRoller::new(vec![]);
+        let attack = RangedAttack::new(grach, 10).with_fire_mode(FireMode::ThreeRoundBurst);
+        assert_eq!(
+            ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap_err(),
+            "Weapon 'Grach' (ROF 2) can't fire 3 rounds at once"
+        );
+        let attack = RangedAttack::new(grach, 10)
+            .with_fire_mode(FireMode::Autofire { bullets: 2 })
+            .with_called_shot(HitZone::Head);
+        assert_eq!(
+            ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap_err(),
+            "Autofire can't be a called shot"
+        );
+    }
+
+    #[test]
+    fn test_rounds_fire_from_the_top_of_a_mixed_magazine() {
+        let mut shooter = character("Shooter");
+        shooter.skills.push(Skill::new(
+            "Maschinenpistole".to_string(),
+            Attribute::Reflexes,
+            2,
+            1,
+        ));
+        shooter.learn_weapon("Smg");
+        let uzi = WeaponCatalog::builtin().weapon("Uzi Miniauto 9").unwrap();
+        let uzi_uuid = uzi.item.uuid;
+        shooter.inventory.push(Box::new(uzi));
+        let regular = ammunition("9mm", DamageType::Blunt, 28);
+        let hollow_points = ammunition("9mm", DamageType::HollowPoint, 10);
+        let (regular_uuid, hollow_point_uuid) = (regular.item.uuid, hollow_points.item.uuid);
+        shooter.inventory.push(Box::new(regular));
+        shooter.inventory.push(Box::new(hollow_points));
+
+        // 28 regular rounds, then 2 hollow points on top: the magazine is full
+        assert_eq!(shooter.reload(uzi_uuid, regular_uuid, None), Ok(28));
+        assert_eq!(shooter.reload(uzi_uuid, hollow_point_uuid, None), Ok(2));
+        assert!(shooter.inventory.get_item(regular_uuid).is_none());
+        assert_eq!(
+            shooter
+                .inventory
+                .get_item(hollow_point_uuid)
+                .unwrap()
+                .get_item()
+                .amount,
+            8
+        );
+
+        // burst: 8 + 2 + 1 + 3 = 14, die 2 -> 16 vs 15; both hits; the two
+        // hollow points fire first
+        let mut target = character("Target");
+        let mut roller = SequenceRoller::new(vec![2, 2, 2, 1, 1, 2, 1, 1]);
+        let attack = RangedAttack::new(uzi_uuid, 30).with_fire_mode(FireMode::ThreeRoundBurst);
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap();
+        let types: Vec<DamageType> = report.hits.iter().map(|hit| hit.damage_type).collect();
+        assert_eq!(
+            types,
+            vec![DamageType::HollowPoint, DamageType::HollowPoint]
+        );
+        let uzi = shooter.inventory.get_all_weapons()[0];
+        assert_eq!(uzi.loaded(), 27);
+        assert_eq!(uzi.magazine.len(), 1);
+    }
+
+    #[test]
+    fn test_unload_returns_rounds_to_the_inventory() {
+        let mut shooter = character("Shooter");
+        let uzi = WeaponCatalog::builtin().weapon("Uzi Miniauto 9").unwrap();
+        let uzi_uuid = uzi.item.uuid;
+        shooter.inventory.push(Box::new(uzi));
+        let regular = ammunition("9mm", DamageType::Blunt, 28);
+        let hollow_points = ammunition("9mm", DamageType::HollowPoint, 10);
+        let (regular_uuid, hollow_point_uuid) = (regular.item.uuid, hollow_points.item.uuid);
+        shooter.inventory.push(Box::new(regular));
+        shooter.inventory.push(Box::new(hollow_points));
+        let rounds = |shooter: &Character| -> i32 {
+            let carried: i32 = shooter
+                .inventory
+                .get_all_ammunition()
+                .iter()
+                .map(|ammunition| ammunition.count())
+                .sum();
+            carried + shooter.inventory.get_all_weapons()[0].loaded()
+        };
+
+        shooter.reload(uzi_uuid, regular_uuid, None).unwrap();
+        shooter.reload(uzi_uuid, hollow_point_uuid, None).unwrap();
+        assert_eq!(rounds(&shooter), 38);
+
+        // the regular rounds come back as a new item, the hollow points join
+        // the 8 still carried
+        assert_eq!(shooter.unload(uzi_uuid), Ok(30));
+        assert_eq!(rounds(&shooter), 38);
+        assert_eq!(shooter.inventory.get_all_weapons()[0].loaded(), 0);
+        let mut carried: Vec<(DamageType, i32)> = shooter
+            .inventory
+            .get_all_ammunition()
+            .iter()
+            .map(|ammunition| (ammunition.damage_type, ammunition.count()))
+            .collect();
+        carried.sort_by_key(|&(_, count)| count);
+        assert_eq!(
+            carried,
+            vec![(DamageType::HollowPoint, 10), (DamageType::Blunt, 28)]
+        );
+        assert_eq!(
+            shooter.unload(regular_uuid),
+            Err(format!(
+                "Character 'Shooter' carries no weapon with id {}",
+                regular_uuid
+            ))
+        );
+    }
+
+    #[test]
+    fn test_empty_or_wrong_caliber_weapon_does_not_fire() {
+        let mut shooter = character("Shooter");
+        let grach = WeaponCatalog::builtin().weapon("Grach").unwrap();
+        let grach_uuid = grach.item.uuid;
+        shooter.inventory.push(Box::new(grach));
+        let rifle_rounds = ammunition("7.62x39", DamageType::Blunt, 30);
+        let rifle_rounds_uuid = rifle_rounds.item.uuid;
+        shooter.inventory.push(Box::new(rifle_rounds));
+
+        assert_eq!(
+            shooter.reload(grach_uuid, rifle_rounds_uuid, None),
+            Err("Ammunition '7.62x39 Blunt' (7.62x39) doesn't fit 'Grach' (9mm)".to_string())
+        );
+        let mut target = character("Target");
+        let mut roller = SequenceRoller::new(vec![]);
+        assert_eq!(
+            ranged_attack(
+                &mut shooter,
+                &mut target,
+                &RangedAttack::new(grach_uuid, 10),
+                &mut roller
+            )
+            .unwrap_err(),
+            "Weapon 'Grach' is empty"
+        );
+    }
+
+    #[test]
+    fn test_unfamiliar_weapon_shifts_difficulty_until_practiced() {
+        let mut shooter = character("Shooter");
+        let grach = WeaponCatalog::builtin().weapon("Grach").unwrap();
+        let grach_uuid = grach.item.uuid;
+        shooter.inventory.push(Box::new(grach));
+        let rounds = ammunition("9mm", DamageType::Blunt, 100);
+        let rounds_uuid = rounds.item.uuid;
+        shooter.inventory.push(Box::new(rounds));
+        let mut target = character("Target");
+        let attack = RangedAttack::new(grach_uuid, 10);
+
+        // 8 + 4 + 5 = 17 would hit 15, but not the unfamiliar 18
+        let mut roller = SequenceRoller::new(vec![5]);
+        shooter.reload(grach_uuid, rounds_uuid, None).unwrap();
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap();
+        assert!(!report.check.outcome.is_success());
+        assert_eq!(report.check.target, 18);
+        assert_eq!(
+            report.check.difficulty_shifts,
+            vec![CheckModifier::new(
+                ModifierSource::UnfamiliarWeapon("Grach".to_string()),
+                UNFAMILIAR_WEAPON_SHIFT
+            )]
+        );
+        assert_eq!(shooter.weapon_practice["Grach"], 1);
+
+        // practice makes it familiar
+        for _ in 1..WEAPON_FAMILIARITY_USES {
+            shooter.end_round();
+            let mut roller = SequenceRoller::new(vec![2]);
+            ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap();
+        }
+        assert!(shooter.familiar_weapons.contains(&"Grach".to_string()));
+        assert!(shooter.weapon_practice.is_empty());
+        let mut roller = SequenceRoller::new(vec![2]);
+        let report = ranged_attack(&mut shooter, &mut target, &attack, &mut roller).unwrap();
+        assert_eq!(report.check.target, 15);
+        assert!(report.check.difficulty_shifts.is_empty());
+    }
+
+    #[test]
+    fn test_attack_needs_a_carried_ranged_weapon() {
+        let (mut shooter, knife) = armed_shooter("Combat Knife");
+        let mut target = character("Target");
+        let mut roller = SequenceRoller::new(vec![]);
+        let error = ranged_attack(
+            &mut shooter,
+            &mut target,
+            &RangedAttack::new(knife, 1),
+            &mut roller,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "Weapon 'Combat Knife' is a melee weapon, not a ranged one"
+        );
+
+        let missing = Uuid::new_v4();
+        let error = ranged_attack(
+            &mut shooter,
+            &mut target,
+            &RangedAttack::new(missing, 10),
+            &mut roller,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            format!("Character 'Shooter' carries no weapon with id {}", missing)
+        );
+    }
+
+    #[test]
+    fn test_target_beyond_extreme_range_is_out_of_range() {
+        let (mut shooter, grach) = armed_shooter("Grach");
+        let mut target = character("Target");
+        let mut roller = SequenceRoller::new(vec![]);
+        let error = ranged_attack(
+            &mut shooter,
+            &mut target,
+            &RangedAttack::new(grach, 250),
+            &mut roller,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "A target at 250 m is out of range for 'Grach' (100 m)"
+        );
+    }
+}
@@ -1,28 +1,83 @@
-/// Source of raw d10 rolls.
+use rand::rngs::Xoshiro256PlusPlus;
+use rand::{RngExt, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// Source of raw die rolls.
 ///
 /// The rules engine never calls the RNG directly; it always goes through this
 /// trait so tests can script exact die sequences and a future server can
 /// record and replay rolls.
 pub trait DieRoller {
+    /// Rolls a single die with `sides` faces, returning a value in 1..=sides.
+    fn roll(&mut self, sides: i32) -> i32;
+
     /// Rolls a single d10, returning a value in 1..=10.
-    fn d10(&mut self) -> i32;
+    fn d10(&mut self) -> i32 {
+        self.roll(10)
+    }
+
+    /// Labels the following rolls with what they are for (which character,
+    /// which check, which difficulty). Ignored by rollers that don't record.
+    fn annotate(&mut self, _context: &str) {}
 }
 
-/// The real thing: uniformly random d10s.
+/// The real thing: uniformly random dice.
 pub struct RandomRoller;
 
 impl DieRoller for RandomRoller {
-    fn d10(&mut self) -> i32 {
-        rand::random_range(1..=10)
+    fn roll(&mut self, sides: i32) -> i32 {
+        rand::random_range(1..=sides)
+    }
+}
+
+/// Deterministic dice from an explicit seed: the same seed always yields the
+/// same rolls, so a GM can regenerate an NPC, a combat simulation or a
+/// workshop-trade day from a seed stored alongside the campaign.
+///
+/// The sequence depends on the RNG algorithm (xoshiro256++) and on how the
+/// `rand` version in `Cargo.lock` maps it onto die ranges — an upgrade of
+/// `rand` may change what an old seed produces.
+pub struct SeededRoller {
+    seed: u64,
+    rng: Xoshiro256PlusPlus,
+}
+
+impl SeededRoller {
+    pub fn new(seed: u64) -> Self {
+        SeededRoller {
+            seed,
+            rng: Xoshiro256PlusPlus::seed_from_u64(seed),
+        }
+    }
+
+    /// Starts from a fresh random seed; read it back via
+    /// [`SeededRoller::seed`] to store it.
+    pub fn with_random_seed() -> Self {
+        // TOML integers are signed 64 bit: keep the seed storable
+        Self::new(rand::random_range(0..=i64::MAX as u64))
+    }
+
+    /// The seed this roller started from.
+    pub fn seed(&self) -> u64 {
+        self.seed
     }
 }
 
-/// Scripted roller for tests and replays: returns the given values in order.
+impl DieRoller for SeededRoller {
+    fn roll(&mut self, sides: i32) -> i32 {
+        self.rng.random_range(1..=sides)
+    }
+}
+
+/// Scripted roller for tests: returns the given values in order.
 ///
 /// # Panics
 ///
 /// Panics when more rolls are requested than values were provided, or when a
-/// value is outside 1..=10.
+/// value is outside the range of the requested die.
 pub struct SequenceRoller {
     values: Vec<i32>,
     next: usize,
@@ -35,14 +90,15 @@ impl SequenceRoller {
 }
 
 impl DieRoller for SequenceRoller {
-    fn d10(&mut self) -> i32 {
+    fn roll(&mut self, sides: i32) -> i32 {
         let value = *self
             .values
             .get(self.next)
             .expect("SequenceRoller ran out of scripted values");
         assert!(
-            (1..=10).contains(&value),
-            "SequenceRoller value out of d10 range: {}",
+            (1..=sides).contains(&value),
+            "SequenceRoller value out of d{} range: {}",
+            sides,
             value
         );
         self.next += 1;
@@ -50,6 +106,217 @@ impl DieRoller for SequenceRoller {
     }
 }
 
+/// One die as rolled during a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRoll {
+    pub sides: i32,
+    pub value: i32,
+    /// What the die was rolled for, e.g. "Shooter: Pistole vs 15".
+    /// Empty if nothing was annotated yet.
+    pub context: String,
+}
+
+/// Every die of a session in roll order, as written by a [`RecordingRoller`].
+/// Serializable, so a disputed session can be stored and reproduced exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollLog {
+    pub rolls: Vec<RecordedRoll>,
+}
+
+impl RollLog {
+    /// A roller that feeds the recorded values back in the same order.
+    /// Running the same calls against it reproduces the session.
+    pub fn replay(&self) -> ReplayRoller {
+        ReplayRoller {
+            rolls: self.rolls.clone(),
+            next: 0,
+        }
+    }
+}
+
+/// Plays a [`RollLog`] back die by die, see [`RollLog::replay`].
+///
+/// # Panics
+///
+/// Panics as soon as the calls diverge from the recorded session: more dice
+/// than recorded, or a die with other sides than the recorded one.
+pub struct ReplayRoller {
+    rolls: Vec<RecordedRoll>,
+    next: usize,
+}
+
+impl DieRoller for ReplayRoller {
+    fn roll(&mut self, sides: i32) -> i32 {
+        let recorded = self
+            .rolls
+            .get(self.next)
+            .expect("ReplayRoller ran out of recorded rolls");
+        assert!(
+            recorded.sides == sides,
+            "ReplayRoller diverged at roll {} ({}): d{} requested, d{} recorded",
+            self.next + 1,
+            recorded.context,
+            sides,
+            recorded.sides
+        );
+        self.next += 1;
+        recorded.value
+    }
+}
+
+/// Wraps any roller and records every die it produces, labeled with the
+/// context last passed to [`DieRoller::annotate`].
+pub struct RecordingRoller<R: DieRoller> {
+    inner: R,
+    context: String,
+    log: RollLog,
+}
+
+impl<R: DieRoller> RecordingRoller<R> {
+    pub fn new(inner: R) -> Self {
+        RecordingRoller {
+            inner,
+            context: String::new(),
+            log: RollLog::default(),
+        }
+    }
+
+    pub fn log(&self) -> &RollLog {
+        &self.log
+    }
+
+    pub fn into_log(self) -> RollLog {
+        self.log
+    }
+}
+
+impl<R: DieRoller> DieRoller for RecordingRoller<R> {
+    fn roll(&mut self, sides: i32) -> i32 {
+        let value = self.inner.roll(sides);
+        self.log.rolls.push(RecordedRoll {
+            sides,
+            value,
+            context: self.context.clone(),
+        });
+        value
+    }
+
+    fn annotate(&mut self, context: &str) {
+        self.context = context.to_string();
+        self.inner.annotate(context);
+    }
+}
+
+/// A dice expression in the usual notation: `5d6`, `2d6+1`, `1d3-2`, `1d100`.
+///
+/// Weapons, martial arts, burst hits and crippling rolls all need dice other
+/// than the d10; they roll them through this type so a [`SequenceRoller`] can
+/// script every die size. Serializes as its notation string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct DiceExpression {
+    pub count: i32,
+    pub sides: i32,
+    pub modifier: i32,
+}
+
+/// Result of rolling a [`DiceExpression`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceRoll {
+    /// Natural die values in roll order.
+    pub die_rolls: Vec<i32>,
+    /// Sum of the dice plus the modifier. Can drop below 0 with a negative
+    /// modifier; clamping is up to the caller.
+    pub total: i32,
+}
+
+impl DiceExpression {
+    /// # Panics
+    ///
+    /// Panics if `count` or `sides` is smaller than 1.
+    pub fn new(count: i32, sides: i32, modifier: i32) -> Self {
+        assert!(
+            count >= 1,
+            "Dice expression needs at least one die, got {}",
+            count
+        );
+        assert!(sides >= 1, "Dice need at least one side, got {}", sides);
+        DiceExpression {
+            count,
+            sides,
+            modifier,
+        }
+    }
+
+    pub fn roll(&self, roller: &mut dyn DieRoller) -> DiceRoll {
+        let die_rolls: Vec<i32> = (0..self.count).map(|_| roller.roll(self.sides)).collect();
+        let total = die_rolls.iter().sum::<i32>() + self.modifier;
+        DiceRoll { die_rolls, total }
+    }
+}
+
+impl FromStr for DiceExpression {
+    type Err = String;
+
+    /// Parses `NdM`, `NdM+K` or `NdM-K` (case-insensitive, `dM` means `1dM`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid dice expression: '{}'", s);
+        let normalized: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+        let (count, rest) = normalized.split_once('d').ok_or_else(invalid)?;
+        let count = if count.is_empty() {
+            1
+        } else {
+            count.parse::<i32>().map_err(|_| invalid())?
+        };
+        let (sides, modifier) = match rest.find(['+', '-']) {
+            Some(index) => {
+                let (sides, modifier) = rest.split_at(index);
+                let modifier = modifier.parse::<i32>().map_err(|_| invalid())?;
+                (sides, modifier)
+            }
+            None => (rest, 0),
+        };
+        let sides = sides.parse::<i32>().map_err(|_| invalid())?;
+        if count < 1 || sides < 1 {
+            return Err(invalid());
+        }
+        Ok(DiceExpression {
+            count,
+            sides,
+            modifier,
+        })
+    }
+}
+
+impl TryFrom<String> for DiceExpression {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<DiceExpression> for String {
+    fn from(dice: DiceExpression) -> Self {
+        dice.to_string()
+    }
+}
+
+impl fmt::Display for DiceExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)?;
+        match self.modifier {
+            0 => Ok(()),
+            modifier if modifier > 0 => write!(f, "+{}", modifier),
+            modifier => write!(f, "{}", modifier),
+        }
+    }
+}
+
 /// Standard difficulties from the house rules: easy 10+, normal 15+, hard 20+.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Difficulty {
@@ -91,6 +358,151 @@ impl Outcome {
     }
 }
 
+/// Where a part of a check's value comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModifierSource {
+    /// The attribute's sheet value, by attribute name.
+    Attribute(String),
+    /// The skill level, by skill name.
+    Skill(String),
+    /// An advantage or disadvantage, by name.
+    Advantage(String),
+    /// The wound penalty of the current wound state, by state name.
+    WoundState(String),
+    /// Carried weight (REF and MOV).
+    Encumbrance,
+    /// Worn armor (REF only).
+    ArmorEncumbrance,
+    /// Effective attributes never drop below 0 (Q19): gives back what the
+    /// maluses took beyond that.
+    AttributeFloor,
+    /// Malus from pure bruise damage (Prellschaden), consumed by this roll.
+    PendingRollMalus,
+    /// −3 per extra action declared this round.
+    MultipleActions,
+    /// A supporter's +1 (Teamaktionen), by name.
+    Supporter(String),
+    /// A circumstance of this one roll ("prone", "tripod", "head shot", …).
+    Situational(String),
+    /// Weapon accuracy (WA) of the weapon used, by weapon name.
+    WeaponAccuracy(String),
+    /// A weapon model the character isn't familiar with.
+    UnfamiliarWeapon(String),
+    /// Luck points committed to the roll.
+    Luck,
+}
+
+impl fmt::Display for ModifierSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModifierSource::Attribute(name)
+            | ModifierSource::Skill(name)
+            | ModifierSource::Advantage(name) => write!(f, "{}", name),
+            ModifierSource::WoundState(state) => write!(f, "wounds ({})", state),
+            ModifierSource::Encumbrance => write!(f, "encumbrance"),
+            ModifierSource::ArmorEncumbrance => write!(f, "armor encumbrance"),
+            ModifierSource::AttributeFloor => write!(f, "attribute floor"),
+            ModifierSource::PendingRollMalus => write!(f, "Prellschaden"),
+            ModifierSource::MultipleActions => write!(f, "multiple actions"),
+            ModifierSource::Supporter(name) => write!(f, "supporter {}", name),
+            ModifierSource::Situational(label) => write!(f, "{}", label),
+            ModifierSource::WeaponAccuracy(name) => write!(f, "{} WA", name),
+            ModifierSource::UnfamiliarWeapon(name) => write!(f, "unfamiliar {}", name),
+            ModifierSource::Luck => write!(f, "luck"),
+        }
+    }
+}
+
+/// One itemized part of a check's value, e.g. `-2 wounds (Serious)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckModifier {
+    pub source: ModifierSource,
+    pub value: i32,
+}
+
+impl CheckModifier {
+    pub fn new(source: ModifierSource, value: i32) -> Self {
+        CheckModifier { source, value }
+    }
+}
+
+impl fmt::Display for CheckModifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:+} {}", self.value, self.source)
+    }
+}
+
+/// The circumstances of a single check, as the caller (or GM) sees them.
+///
+/// Roll bonuses and maluses ("+1 prone", "−4 head shot") are added to the
+/// roll; difficulty shifts ("+3 unfamiliar weapon") raise or lower the
+/// target instead. Tags name the situation ("hören", "initiative", …) so the
+/// character's tagged advantage modifiers are pulled in automatically.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Situation {
+    pub bonuses: Vec<CheckModifier>,
+    pub difficulty_shifts: Vec<CheckModifier>,
+    pub tags: Vec<String>,
+}
+
+impl Situation {
+    pub fn new() -> Self {
+        Situation::default()
+    }
+
+    /// Adds a labelled bonus (or, negative, malus) to the roll.
+    pub fn with_bonus(self, label: &str, value: i32) -> Self {
+        self.with_modifier(CheckModifier::new(
+            ModifierSource::Situational(label.to_string()),
+            value,
+        ))
+    }
+
+    /// Adds an already sourced modifier to the roll.
+    pub fn with_modifier(mut self, modifier: CheckModifier) -> Self {
+        self.bonuses.push(modifier);
+        self
+    }
+
+    /// Raises (or, negative, lowers) the target by a labelled amount.
+    pub fn with_difficulty(mut self, label: &str, value: i32) -> Self {
+        self.difficulty_shifts.push(CheckModifier::new(
+            ModifierSource::Situational(label.to_string()),
+            value,
+        ));
+        self
+    }
+
+    /// Shifts the target by an already sourced amount.
+    pub fn with_difficulty_shift(mut self, shift: CheckModifier) -> Self {
+        self.difficulty_shifts.push(shift);
+        self
+    }
+
+    /// Names a situation tag whose advantage modifiers apply to the roll.
+    /// A tag named twice still counts once.
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        if !self.tags.iter().any(|known| known == tag) {
+            self.tags.push(tag.to_string());
+        }
+        self
+    }
+
+    /// `difficulty` with all difficulty shifts applied.
+    pub fn shifted(&self, difficulty: Difficulty) -> Difficulty {
+        let shift: i32 = self
+            .difficulty_shifts
+            .iter()
+            .map(|modifier| modifier.value)
+            .sum();
+        if shift == 0 {
+            difficulty
+        } else {
+            Difficulty::Custom(difficulty.target() + shift)
+        }
+    }
+}
+
 /// Full record of a skill check, kept for display and later replay.
 #[derive(Debug, PartialEq, Eq)]
 pub struct CheckResult {
@@ -102,6 +514,20 @@ pub struct CheckResult {
     pub die_rolls: Vec<i32>,
     /// The severity die after a fumble, if one happened.
     pub fumble_confirmation: Option<i32>,
+    /// Every source of attribute, skill and luck, itemized; together with
+    /// the natural dice they add up to `total`. Filled by the
+    /// [`Character`](crate::Character) checks, empty for a bare
+    /// [`skill_check`].
+    pub modifiers: Vec<CheckModifier>,
+    /// Situational shifts of the difficulty, already included in `target`.
+    pub difficulty_shifts: Vec<CheckModifier>,
+}
+
+impl CheckResult {
+    /// Sum of the itemized modifiers, i.e. `total` without the dice.
+    pub fn modifier_total(&self) -> i32 {
+        self.modifiers.iter().map(|modifier| modifier.value).sum()
+    }
 }
 
 /// Rolls a skill check against a difficulty, applying all dice house rules:
@@ -132,6 +558,8 @@ pub fn skill_check(
             target,
             die_rolls: Vec::new(),
             fumble_confirmation: None,
+            modifiers: Vec::new(),
+            difficulty_shifts: Vec::new(),
         };
     }
 
@@ -152,6 +580,8 @@ pub fn skill_check(
             target,
             die_rolls,
             fumble_confirmation: Some(confirmation),
+            modifiers: Vec::new(),
+            difficulty_shifts: Vec::new(),
         };
     }
 
@@ -175,7 +605,82 @@ pub fn skill_check(
         target,
         die_rolls,
         fumble_confirmation: None,
+        modifiers: Vec::new(),
+        difficulty_shifts: Vec::new(),
+    }
+}
+
+/// Exact chances of every [`Outcome`] of a [`skill_check`]; they sum to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CheckProbabilities {
+    pub auto_success: f64,
+    pub success: f64,
+    pub failure: f64,
+    pub embarrassing_failure: f64,
+    pub critical_failure: f64,
+}
+
+impl CheckProbabilities {
+    /// Chance to succeed at all (auto-success included).
+    pub fn success_chance(&self) -> f64 {
+        self.auto_success + self.success
+    }
+}
+
+/// Computes the outcome probabilities of [`skill_check`] analytically,
+/// applying the same house rules: auto-success, luck shifting the first die
+/// (both into an explosion and out of a fumble), exploding 10s and the
+/// fumble confirmation die.
+pub fn skill_check_probabilities(
+    attribute: i32,
+    skill: i32,
+    luck: i32,
+    difficulty: Difficulty,
+) -> CheckProbabilities {
+    let mut probabilities = CheckProbabilities {
+        auto_success: 0.0,
+        success: 0.0,
+        failure: 0.0,
+        embarrassing_failure: 0.0,
+        critical_failure: 0.0,
+    };
+    let target = difficulty.target();
+    if attribute + skill + luck >= target {
+        probabilities.auto_success = 1.0;
+        return probabilities;
+    }
+
+    for first in 1..=10 {
+        let adjusted_first = first + luck;
+        if adjusted_first <= 1 {
+            // confirmation die: 1 critical, 2-5 embarrassing, 6-10 normal failure
+            probabilities.critical_failure += 0.1 * 0.1;
+            probabilities.embarrassing_failure += 0.1 * 0.4;
+            probabilities.failure += 0.1 * 0.5;
+            continue;
+        }
+        let total = attribute + skill + adjusted_first;
+        let chance = if adjusted_first >= 10 {
+            explosion_reaches(target - total)
+        } else if total >= target {
+            1.0
+        } else {
+            0.0
+        };
+        probabilities.success += 0.1 * chance;
+        probabilities.failure += 0.1 * (1.0 - chance);
+    }
+    probabilities
+}
+
+/// Chance that the re-rolls after an exploding 10 add up to at least `needed`.
+fn explosion_reaches(needed: i32) -> f64 {
+    if needed <= 1 {
+        return 1.0;
     }
+    // natural 1-9 end the cascade, a 10 adds and rolls again
+    let ending_high_enough = (needed..=9).count() as f64;
+    0.1 * ending_high_enough + 0.1 * explosion_reaches(needed - 10)
 }
 
 /// Record of an open-ended roll (no target): total speaks for itself.
@@ -188,6 +693,10 @@ pub struct OpenRollResult {
     /// True when the (luck-adjusted) die showed 1: an open roll can still fumble.
     pub is_fumble: bool,
     pub fumble_confirmation: Option<i32>,
+    /// Situational difficulty shifts: without a target to raise they count
+    /// against `total`. Filled by [`Character::open_check_with`](crate::Character::open_check_with),
+    /// empty for a bare [`open_roll`].
+    pub difficulty_shifts: Vec<CheckModifier>,
 }
 
 /// Rolls openly (no difficulty): exploding 10s, luck and fumbles apply,
@@ -209,6 +718,7 @@ pub fn open_roll(
             die_rolls,
             is_fumble: true,
             fumble_confirmation: Some(confirmation),
+            difficulty_shifts: Vec::new(),
         };
     }
 
@@ -225,6 +735,7 @@ pub fn open_roll(
         die_rolls,
         is_fumble: false,
         fumble_confirmation: None,
+        difficulty_shifts: Vec::new(),
     }
 }
 
@@ -322,6 +833,72 @@ mod tests {
         assert_eq!(Difficulty::Custom(35).target(), 35);
     }
 
+    fn assert_chance(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected chance {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_probabilities_plain_check() {
+        // 5 + 4 vs 15: die 6-10 succeeds, 2-5 fails, 1 fumbles
+        let odds = skill_check_probabilities(5, 4, 0, Difficulty::Normal);
+        assert_chance(odds.auto_success, 0.0);
+        assert_chance(odds.success, 0.5);
+        assert_chance(odds.failure, 0.4 + 0.05);
+        assert_chance(odds.embarrassing_failure, 0.04);
+        assert_chance(odds.critical_failure, 0.01);
+        assert_chance(odds.success_chance(), 0.5);
+    }
+
+    #[test]
+    fn test_probabilities_auto_success() {
+        let odds = skill_check_probabilities(7, 6, 2, Difficulty::Normal);
+        assert_chance(odds.auto_success, 1.0);
+        assert_chance(odds.success_chance(), 1.0);
+    }
+
+    #[test]
+    fn test_probabilities_need_explosions() {
+        // 2 vs 20 needs 18 from the dice: a 10, then 8+ (8, 9, or another 10)
+        let odds = skill_check_probabilities(2, 0, 0, Difficulty::Hard);
+        assert_chance(odds.success, 0.1 * 0.3);
+    }
+
+    #[test]
+    fn test_probabilities_luck_shifts_first_die() {
+        // 2 + 2 + 1 luck vs 20: 9+1 explodes needing 6 more (0.5),
+        // 10+1 explodes needing 5 more (0.6); a rolled 1 is no fumble
+        let odds = skill_check_probabilities(2, 2, 1, Difficulty::Hard);
+        assert_chance(odds.success, 0.1 * 0.5 + 0.1 * 0.6);
+        assert_chance(odds.critical_failure, 0.0);
+        assert_chance(odds.embarrassing_failure, 0.0);
+    }
+
+    #[test]
+    fn test_probabilities_match_rolled_checks() {
+        let mut roller = SeededRoller::new(11);
+        let iterations = 200_000;
+        let mut successes = 0;
+        let mut criticals = 0;
+        for _ in 0..iterations {
+            let result = skill_check(4, 3, 1, Difficulty::Custom(22), &mut roller);
+            if result.outcome.is_success() {
+                successes += 1;
+            }
+            if result.outcome == Outcome::CriticalFailure {
+                criticals += 1;
+            }
+        }
+        let odds = skill_check_probabilities(4, 3, 1, Difficulty::Custom(22));
+        let rolled = successes as f64 / iterations as f64;
+        assert!((rolled - odds.success_chance()).abs() < 0.005);
+        assert_eq!(criticals, 0, "1 luck prevents every fumble");
+    }
+
     #[test]
     fn test_open_roll_explodes_and_sums() {
         let mut roller = SequenceRoller::new(vec![10, 4]);
@@ -348,6 +925,166 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dice_expression_parsing() {
+        assert_eq!(
+            "5d6".parse::<DiceExpression>(),
+            Ok(DiceExpression::new(5, 6, 0))
+        );
+        assert_eq!(
+            "2d6+1".parse::<DiceExpression>(),
+            Ok(DiceExpression::new(2, 6, 1))
+        );
+        assert_eq!(
+            "1D3-2".parse::<DiceExpression>(),
+            Ok(DiceExpression::new(1, 3, -2))
+        );
+        assert_eq!(
+            "d100".parse::<DiceExpression>(),
+            Ok(DiceExpression::new(1, 100, 0))
+        );
+        for invalid in ["", "6", "0d6", "2d", "2d6+", "2w6", "2d6+1+1"] {
+            assert_eq!(
+                invalid.parse::<DiceExpression>(),
+                Err(format!("Invalid dice expression: '{}'", invalid))
+            );
+        }
+    }
+
+    #[test]
+    fn test_dice_expression_display_round_trip() {
+        for notation in ["5d6", "2d6+1", "1d3-2", "1d100"] {
+            let dice: DiceExpression = notation.parse().unwrap();
+            assert_eq!(dice.to_string(), notation);
+        }
+    }
+
+    #[test]
+    fn test_dice_expression_rolls_through_roller() {
+        // the scripted roller serves every die size, not just d10s
+        let mut roller = SequenceRoller::new(vec![6, 1, 1]);
+        let result = DiceExpression::new(2, 6, 1).roll(&mut roller);
+        assert_eq!(result.die_rolls, vec![6, 1]);
+        assert_eq!(result.total, 8);
+
+        let result = DiceExpression::new(1, 3, -2).roll(&mut roller);
+        assert_eq!(result.total, -1, "clamping is the caller's job");
+    }
+
+    #[test]
+    #[should_panic(expected = "SequenceRoller value out of d6 range: 7")]
+    fn test_sequence_roller_checks_die_size() {
+        let mut roller = SequenceRoller::new(vec![7]);
+        roller.roll(6);
+    }
+
+    #[test]
+    fn test_dice_expression_serialization() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Wrapper {
+            damage: DiceExpression,
+        }
+        let wrapper = Wrapper {
+            damage: DiceExpression::new(2, 6, 1),
+        };
+        let serialized = toml::to_string(&wrapper).unwrap();
+        assert_eq!(serialized, "damage = \"2d6+1\"\n");
+        let deserialized: Wrapper = toml::from_str(&serialized).unwrap();
+        assert_eq!(wrapper, deserialized);
+    }
+
+    #[test]
+    fn test_recording_roller_labels_every_die() {
+        let mut roller = RecordingRoller::new(SequenceRoller::new(vec![10, 4, 3]));
+        roller.annotate("Shooter: Pistole vs 20");
+        skill_check(2, 0, 0, Difficulty::Hard, &mut roller);
+        roller.annotate("Shooter: damage 1d6");
+        roller.roll(6);
+
+        let log = roller.into_log();
+        assert_eq!(
+            log.rolls,
+            vec![
+                RecordedRoll {
+                    sides: 10,
+                    value: 10,
+                    context: "Shooter: Pistole vs 20".to_string()
+                },
+                RecordedRoll {
+                    sides: 10,
+                    value: 4,
+                    context: "Shooter: Pistole vs 20".to_string()
+                },
+                RecordedRoll {
+                    sides: 6,
+                    value: 3,
+                    context: "Shooter: damage 1d6".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "ReplayRoller diverged at roll 2 (damage): d10 requested, d6 recorded"
+    )]
+    fn test_replay_panics_on_other_dice() {
+        let mut recorder = RecordingRoller::new(SequenceRoller::new(vec![7, 3]));
+        recorder.annotate("to hit");
+        recorder.d10();
+        recorder.annotate("damage");
+        recorder.roll(6);
+
+        let mut replay = recorder.log().replay();
+        replay.d10();
+        replay.d10();
+    }
+
+    #[test]
+    fn test_roll_log_replays_a_session() {
+        let mut recorder = RecordingRoller::new(RandomRoller);
+        let original: Vec<CheckResult> = (0..20)
+            .map(|_| skill_check(5, 4, 1, Difficulty::Hard, &mut recorder))
+            .collect();
+
+        // stored and loaded again, e.g. after a dispute at the table
+        let serialized = toml::to_string(recorder.log()).unwrap();
+        let log: RollLog = toml::from_str(&serialized).unwrap();
+        assert_eq!(&log, recorder.log());
+
+        let mut replay = log.replay();
+        let replayed: Vec<CheckResult> = (0..20)
+            .map(|_| skill_check(5, 4, 1, Difficulty::Hard, &mut replay))
+            .collect();
+        assert_eq!(original, replayed);
+    }
+
+    #[test]
+    fn test_seeded_roller_is_reproducible() {
+        let rolls = |roller: &mut SeededRoller| -> Vec<i32> {
+            (0..50).map(|_| roller.roll(6) + roller.d10()).collect()
+        };
+        let mut first = SeededRoller::new(2020);
+        let mut second = SeededRoller::new(2020);
+        assert_eq!(rolls(&mut first), rolls(&mut second));
+
+        let mut other = SeededRoller::new(2077);
+        assert_ne!(rolls(&mut SeededRoller::new(2020)), rolls(&mut other));
+    }
+
+    #[test]
+    fn test_seeded_roller_reports_its_seed() {
+        let mut roller = SeededRoller::with_random_seed();
+        let seed = roller.seed();
+        let original: Vec<i32> = (0..20).map(|_| roller.d10()).collect();
+        assert_eq!(roller.seed(), seed, "rolling doesn't change the seed");
+
+        let mut regenerated = SeededRoller::new(seed);
+        let replayed: Vec<i32> = (0..20).map(|_| regenerated.d10()).collect();
+        assert_eq!(original, replayed);
+        assert!(original.iter().all(|roll| (1..=10).contains(roll)));
+    }
+
     #[test]
     #[should_panic(expected = "ran out of scripted values")]
     fn test_sequence_roller_exhaustion_panics() {
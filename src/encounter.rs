@@ -0,0 +1,444 @@
+use crate::character::{Attribute, Character};
+use crate::dice::{CheckResult, DieRoller, Situation};
+use crate::health::WoundState;
+use std::fmt;
+
+/// Quick draw (Schnellziehen): added to the initiative roll.
+pub const QUICK_DRAW_INITIATIVE_BONUS: i32 = 3;
+/// Quick draw: malus on the quick-drawing combatant's actions that round.
+pub const QUICK_DRAW_ACTION_MALUS: i32 = 3;
+
+/// Which side of the table plays a combatant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombatantKind {
+    Pc,
+    Npc,
+}
+
+/// Whether a combatant still takes turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombatStatus {
+    Active,
+    /// Failed a KO check: skipped until a KO check succeeds.
+    KnockedOut,
+    /// Dead by [`WoundState::Dead`]: out of the turn order for good.
+    Dead,
+}
+
+impl fmt::Display for CombatStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A character taking part in an [`Encounter`].
+#[derive(Debug)]
+pub struct Combatant {
+    pub character: Character,
+    pub kind: CombatantKind,
+    /// The last initiative roll, quick draw included.
+    pub initiative: Option<i32>,
+    /// Quick draw declared for the next initiative roll.
+    pub quick_draw_declared: bool,
+    /// Quick-drawing this round.
+    pub quick_draw: bool,
+    pub status: CombatStatus,
+}
+
+impl Combatant {
+    pub fn can_act(&self) -> bool {
+        self.status == CombatStatus::Active
+    }
+}
+
+/// A fight between PCs and NPCs, from initiative to the last one standing.
+///
+/// [`Encounter::roll_initiative`] rolls for everyone still alive and sorts
+/// the turn order: highest initiative first, ties going to the higher
+/// effective REF and then to whoever joined first (Q39).
+/// [`Encounter::next_turn`] walks the order, skipping knocked-out
+/// combatants, and starts the next round (same order) after the last one;
+/// [`Encounter::roll_initiative`] again re-rolls for a new round instead.
+/// The dead leave the order as soon as their wound state says so.
+#[derive(Debug, Default)]
+pub struct Encounter {
+    combatants: Vec<Combatant>,
+    /// Indices into `combatants`, in turn order.
+    order: Vec<usize>,
+    /// Position in `order` of the combatant whose turn it is.
+    turn: Option<usize>,
+    round: i32,
+}
+
+impl Encounter {
+    pub fn new() -> Self {
+        Encounter::default()
+    }
+
+    /// Adds a combatant; joining a running fight, they get into the turn
+    /// order with the next initiative roll.
+    pub fn add(&mut self, character: Character, kind: CombatantKind) -> Result<(), String> {
+        if self.position(&character.name).is_some() {
+            return Err(format!(
+                "'{}' already takes part in the encounter",
+                character.name
+            ));
+        }
+        let status = if character.wound_state() == WoundState::Dead {
+            CombatStatus::Dead
+        } else {
+            CombatStatus::Active
+        };
+        self.combatants.push(Combatant {
+            character,
+            kind,
+            initiative: None,
+            quick_draw_declared: false,
+            quick_draw: false,
+            status,
+        });
+        Ok(())
+    }
+
+    pub fn combatants(&self) -> &[Combatant] {
+        &self.combatants
+    }
+
+    pub fn combatant(&self, name: &str) -> Result<&Combatant, String> {
+        let index = self.index(name)?;
+        Ok(&self.combatants[index])
+    }
+
+    pub fn character_mut(&mut self, name: &str) -> Result<&mut Character, String> {
+        let index = self.index(name)?;
+        Ok(&mut self.combatants[index].character)
+    }
+
+    /// Two different combatants at once, e.g. attacker and defender.
+    pub fn pair_mut(
+        &mut self,
+        first: &str,
+        second: &str,
+    ) -> Result<(&mut Character, &mut Character), String> {
+        let first = self.index(first)?;
+        let second = self.index(second)?;
+        if first == second {
+            return Err(format!(
+                "'{}' can't face themselves",
+                self.combatants[first].character.name
+            ));
+        }
+        if first < second {
+            let (left, right) = self.combatants.split_at_mut(second);
+            Ok((&mut left[first].character, &mut right[0].character))
+        } else {
+            let (left, right) = self.combatants.split_at_mut(first);
+            Ok((&mut right[0].character, &mut left[second].character))
+        }
+    }
+
+    /// The current round, 0 before the first initiative roll.
+    pub fn round(&self) -> i32 {
+        self.round
+    }
+
+    /// Names of everyone in the turn order, knocked-out combatants included.
+    pub fn turn_order(&self) -> Vec<&str> {
+        self.order
+            .iter()
+            .map(|&index| self.combatants[index].character.name.as_str())
+            .collect()
+    }
+
+    /// Whose turn it is.
+    pub fn current(&self) -> Option<&Combatant> {
+        self.turn.map(|turn| &self.combatants[self.order[turn]])
+    }
+
+    /// Declares a quick draw for the next initiative roll:
+    /// +[`QUICK_DRAW_INITIATIVE_BONUS`] on the roll,
+    /// −[`QUICK_DRAW_ACTION_MALUS`] on the actions of that round.
+    pub fn declare_quick_draw(&mut self, name: &str) -> Result<(), String> {
+        let index = self.index(name)?;
+        let combatant = &mut self.combatants[index];
+        if !combatant.can_act() {
+            return Err(format!(
+                "'{}' can't draw: {}",
+                combatant.character.name, combatant.status
+            ));
+        }
+        combatant.quick_draw_declared = true;
+        Ok(())
+    }
+
+    /// Starts a new round with fresh initiative for everyone still alive and
+    /// returns whose turn it is first.
+    pub fn roll_initiative(&mut self, roller: &mut dyn DieRoller) -> Option<&Combatant> {
+        if self.round > 0 {
+            self.end_round();
+        }
+        self.refresh_statuses();
+        for combatant in &mut self.combatants {
+            combatant.quick_draw = combatant.quick_draw_declared;
+            combatant.quick_draw_declared = false;
+            combatant.initiative = if combatant.status == CombatStatus::Dead {
+                None
+            } else {
+                let mut initiative = combatant.character.roll_initiative(roller);
+                if combatant.quick_draw {
+                    initiative += QUICK_DRAW_INITIATIVE_BONUS;
+                }
+                Some(initiative)
+            };
+        }
+        let combatants = &self.combatants;
+        let mut order: Vec<usize> = (0..combatants.len())
+            .filter(|&index| combatants[index].initiative.is_some())
+            .collect();
+        // stable: full ties stay in the order the combatants joined
+        order.sort_by_key(|&index| {
+            let combatant = &combatants[index];
+            std::cmp::Reverse((
+                combatant.initiative,
+                combatant.character.effective_attribute(Attribute::Reflexes),
+            ))
+        });
+        self.order = order;
+        self.round += 1;
+        self.turn = None;
+        self.advance()
+    }
+
+    /// Ends the current turn and returns whose turn it is next. After the
+    /// last combatant of the round the next round starts in the same order.
+    /// `None` if nobody can act any more.
+    pub fn next_turn(&mut self) -> Option<&Combatant> {
+        self.refresh_statuses();
+        self.advance()
+    }
+
+    /// The situation of `name`'s actions this round: the quick draw malus,
+    /// if they drew quickly.
+    pub fn action_situation(&self, name: &str) -> Result<Situation, String> {
+        let combatant = self.combatant(name)?;
+        let mut situation = Situation::new();
+        if combatant.quick_draw {
+            situation = situation.with_bonus("quick draw", -QUICK_DRAW_ACTION_MALUS);
+        }
+        Ok(situation)
+    }
+
+    /// Rolls `name`'s KO check (see [`Character::ko_check`]): a failure
+    /// knocks them out, the first success afterwards brings them back.
+    pub fn ko_check(
+        &mut self,
+        name: &str,
+        roller: &mut dyn DieRoller,
+    ) -> Result<CheckResult, String> {
+        let index = self.index(name)?;
+        let combatant = &mut self.combatants[index];
+        if combatant.status == CombatStatus::Dead {
+            return Err(format!("'{}' is dead", combatant.character.name));
+        }
+        let result = combatant.character.ko_check(roller);
+        combatant.status = if result.outcome.is_success() {
+            CombatStatus::Active
+        } else {
+            CombatStatus::KnockedOut
+        };
+        Ok(result)
+    }
+
+    /// Whether only one side (or nobody) can still act.
+    pub fn is_decided(&self) -> bool {
+        let standing = |kind: CombatantKind| {
+            self.combatants
+                .iter()
+                .any(|combatant| combatant.kind == kind && combatant.can_act())
+        };
+        !(standing(CombatantKind::Pc) && standing(CombatantKind::Npc))
+    }
+
+    /// Moves to the next combatant who can act, wrapping into a new round.
+    fn advance(&mut self) -> Option<&Combatant> {
+        let combatants = &self.combatants;
+        if !self.order.iter().any(|&index| combatants[index].can_act()) {
+            self.turn = None;
+            return None;
+        }
+        let mut turn = self.turn.map_or(0, |turn| turn + 1);
+        loop {
+            if turn >= self.order.len() {
+                self.end_round();
+                self.round += 1;
+                turn = 0;
+            }
+            if self.combatants[self.order[turn]].can_act() {
+                break;
+            }
+            turn += 1;
+        }
+        self.turn = Some(turn);
+        self.current()
+    }
+
+    /// Declared actions, ambushes and quick draws expire with the round.
+    fn end_round(&mut self) {
+        for combatant in &mut self.combatants {
+            combatant.character.end_round();
+            combatant.quick_draw = false;
+        }
+    }
+
+    /// Flags the dead and takes them out of the turn order.
+    fn refresh_statuses(&mut self) {
+        for combatant in &mut self.combatants {
+            if combatant.character.wound_state() == WoundState::Dead {
+                combatant.status = CombatStatus::Dead;
+            }
+        }
+        let combatants = &self.combatants;
+        let removed_up_to_turn = match self.turn {
+            Some(turn) => self.order[..=turn]
+                .iter()
+                .filter(|&&index| combatants[index].status == CombatStatus::Dead)
+                .count(),
+            None => 0,
+        };
+        self.order
+            .retain(|&index| combatants[index].status != CombatStatus::Dead);
+        // a dead current combatant hands the turn on to the next one up
+        self.turn = self
+            .turn
+            .and_then(|turn| turn.checked_sub(removed_up_to_turn));
+    }
+
+    fn index(&self, name: &str) -> Result<usize, String> {
+        self.position(name)
+            .ok_or_else(|| format!("'{}' doesn't take part in the encounter", name))
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.combatants
+            .iter()
+            .position(|combatant| combatant.character.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::armor::HitZone;
+    use crate::character::tests::{set_attribute, solo};
+    use crate::dice::SequenceRoller;
+
+    fn character(name: &str, reflexes: i32) -> Character {
+        let mut character = solo(name);
+        set_attribute(&mut character, Attribute::Reflexes, reflexes);
+        character
+    }
+
+    fn encounter() -> Encounter {
+        let mut encounter = Encounter::new();
+        encounter
+            .add(character("Pc", 6), CombatantKind::Pc)
+            .unwrap();
+        encounter
+            .add(character("Ganger", 8), CombatantKind::Npc)
+            .unwrap();
+        encounter
+            .add(character("Boss", 5), CombatantKind::Npc)
+            .unwrap();
+        encounter
+    }
+
+    #[test]
+    fn test_initiative_order_with_ref_tiebreak_and_quick_draw() {
+        let mut encounter = encounter();
+        encounter.declare_quick_draw("Boss").unwrap();
+        // Pc 6 + 6 = 12, Ganger 8 + 4 = 12, Boss 5 + 4 + 3 = 12
+        let mut roller = SequenceRoller::new(vec![6, 4, 4]);
+        let first = encounter.roll_initiative(&mut roller).unwrap();
+        assert_eq!(first.character.name, "Ganger");
+        assert_eq!(encounter.turn_order(), vec!["Ganger", "Pc", "Boss"]);
+        assert_eq!(encounter.combatant("Boss").unwrap().initiative, Some(12));
+        assert_eq!(encounter.round(), 1);
+
+        let situation = encounter.action_situation("Boss").unwrap();
+        assert_eq!(situation.bonuses[0].to_string(), "-3 quick draw");
+        assert!(encounter.action_situation("Pc").unwrap().bonuses.is_empty());
+        assert_eq!(
+            encounter.declare_quick_draw("Nobody").unwrap_err(),
+            "'Nobody' doesn't take part in the encounter"
+        );
+        assert_eq!(
+            encounter
+                .add(character("Pc", 6), CombatantKind::Pc)
+                .unwrap_err(),
+            "'Pc' already takes part in the encounter"
+        );
+    }
+
+    #[test]
+    fn test_turns_and_rounds_skip_the_knocked_out_and_drop_the_dead() {
+        let mut encounter = encounter();
+        let mut roller = SequenceRoller::new(vec![6, 4, 4]);
+        encounter.roll_initiative(&mut roller);
+        encounter.declare_quick_draw("Boss").unwrap();
+        let name = |combatant: Option<&Combatant>| combatant.unwrap().character.name.clone();
+        assert_eq!(name(encounter.next_turn()), "Pc");
+        assert_eq!(name(encounter.next_turn()), "Boss");
+        assert_eq!(name(encounter.next_turn()), "Ganger");
+        assert_eq!(encounter.round(), 2);
+        // declared for the next roll, not this round
+        assert!(encounter
+            .action_situation("Boss")
+            .unwrap()
+            .bonuses
+            .is_empty());
+
+        // 9 damage, BTM 4 -> 5: serious, KO check at -1; die 1 fumbles
+        encounter
+            .character_mut("Pc")
+            .unwrap()
+            .take_damage(9, HitZone::Chest);
+        let mut roller = SequenceRoller::new(vec![1, 8]);
+        let check = encounter.ko_check("Pc", &mut roller).unwrap();
+        assert!(!check.outcome.is_success());
+        assert_eq!(
+            encounter.combatant("Pc").unwrap().status,
+            CombatStatus::KnockedOut
+        );
+        assert_eq!(name(encounter.next_turn()), "Boss");
+        // the only Pc is down
+        assert!(encounter.is_decided());
+
+        // the Ganger dies before their turn: out of the order
+        encounter
+            .character_mut("Ganger")
+            .unwrap()
+            .take_damage(50, HitZone::Chest);
+        assert_eq!(name(encounter.next_turn()), "Boss");
+        assert_eq!(encounter.round(), 3);
+        assert_eq!(encounter.turn_order(), vec!["Pc", "Boss"]);
+        assert_eq!(
+            encounter.combatant("Ganger").unwrap().status,
+            CombatStatus::Dead
+        );
+        assert_eq!(
+            encounter.ko_check("Ganger", &mut roller).unwrap_err(),
+            "'Ganger' is dead"
+        );
+    }
+
+    #[test]
+    fn test_pairs_for_attacks() {
+        let mut encounter = encounter();
+        let (boss, pc) = encounter.pair_mut("Boss", "Pc").unwrap();
+        assert_eq!((boss.name.as_str(), pc.name.as_str()), ("Boss", "Pc"));
+        assert_eq!(
+            encounter.pair_mut("Pc", "Pc").unwrap_err(),
+            "'Pc' can't face themselves"
+        );
+    }
+}
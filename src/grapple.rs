@@ -0,0 +1,408 @@
+use std::fmt;
+
+use crate::armor::HitZone;
+use crate::character::Character;
+use crate::dice::{DieRoller, Situation};
+use crate::martial_arts::{
+    choke, martial_arts_attack, MartialAction, MartialArtsAttack, MartialArtsReport,
+    MartialArtsStyle, HOLD_BONUS_PER_ROUND,
+};
+use crate::melee::{skill_or_reflexes, MeleeDefense, MeleeHit};
+use crate::opposed::{resolve_opposed, OpposedResult, OpposedWinner};
+
+/// How far a grapple has gone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrappleStage {
+    /// Got hold of the target: Throw or Hold may follow.
+    Grappled,
+    /// The target is immobilized: Choke or Throw may follow.
+    Held,
+    /// The held target takes choke damage every round.
+    Choking,
+    /// Over: the target was thrown or escaped.
+    Released,
+}
+
+impl fmt::Display for GrappleStage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A grapple between two characters (by name) that lasts across rounds:
+/// Grapple → Hold → Choke, with Throw ending it from either of the first
+/// two and Escape as the target's counter at any time.
+///
+/// Follow-ups are rolled against an easy (10) check: a grappled target
+/// can't dodge. What the target can do is escape, against the holder's
+/// style roll — plus the style's Hold bonus once held and +1 for every
+/// round the hold lasted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grapple {
+    pub holder: String,
+    pub target: String,
+    pub style: MartialArtsStyle,
+    pub stage: GrappleStage,
+    /// Rounds the hold has been kept up, see [`Grapple::next_round`].
+    pub rounds_held: i32,
+    /// The zone a choke damages, set by the hold.
+    pub zone: HitZone,
+}
+
+impl Grapple {
+    /// Rolls a Grapple of `holder` against the target's `defense`; the
+    /// grapple starts if it works.
+    pub fn attempt(
+        holder: &mut Character,
+        target: &mut Character,
+        style: MartialArtsStyle,
+        defense: MeleeDefense,
+        roller: &mut dyn DieRoller,
+    ) -> Result<(MartialArtsReport, Option<Grapple>), String> {
+        let attack = MartialArtsAttack::new(style, MartialAction::Grapple, defense);
+        let report = martial_arts_attack(holder, target, &attack, roller)?;
+        let grapple = if report.succeeded() {
+            Some(Grapple {
+                holder: holder.name.clone(),
+                target: target.name.clone(),
+                style,
+                stage: GrappleStage::Grappled,
+                rounds_held: 0,
+                zone: HitZone::Chest,
+            })
+        } else {
+            None
+        };
+        Ok((report, grapple))
+    }
+
+    /// Turns the grapple into a hold on `zone`, the zone a later choke
+    /// damages.
+    pub fn hold(
+        &mut self,
+        holder: &mut Character,
+        target: &mut Character,
+        zone: HitZone,
+        roller: &mut dyn DieRoller,
+    ) -> Result<MartialArtsReport, String> {
+        self.follow_up(
+            holder,
+            target,
+            MartialAction::Hold,
+            &[GrappleStage::Grappled],
+        )?;
+        let report = self.roll(holder, target, MartialAction::Hold, roller)?;
+        if report.succeeded() {
+            self.stage = GrappleStage::Held;
+            self.zone = zone;
+        }
+        Ok(report)
+    }
+
+    /// Starts choking the held target; the first round of damage comes
+    /// with it, see [`Grapple::next_round`] for the following ones.
+    pub fn choke(
+        &mut self,
+        holder: &mut Character,
+        target: &mut Character,
+        roller: &mut dyn DieRoller,
+    ) -> Result<MartialArtsReport, String> {
+        self.follow_up(holder, target, MartialAction::Choke, &[GrappleStage::Held])?;
+        let report = self.roll(holder, target, MartialAction::Choke, roller)?;
+        if report.succeeded() {
+            self.stage = GrappleStage::Choking;
+        }
+        Ok(report)
+    }
+
+    /// Throws the grappled or held target, which ends the grapple if it
+    /// works.
+    pub fn throw(
+        &mut self,
+        holder: &mut Character,
+        target: &mut Character,
+        roller: &mut dyn DieRoller,
+    ) -> Result<MartialArtsReport, String> {
+        self.follow_up(
+            holder,
+            target,
+            MartialAction::Throw,
+            &[GrappleStage::Grappled, GrappleStage::Held],
+        )?;
+        let report = self.roll(holder, target, MartialAction::Throw, roller)?;
+        if report.succeeded() {
+            self.stage = GrappleStage::Released;
+        }
+        Ok(report)
+    }
+
+    /// The target tries to break free with `style` (bare REF without the
+    /// skill), openly against the holder. The grapple ends if the target
+    /// wins; ties go to the holder.
+    pub fn escape(
+        &mut self,
+        target: &mut Character,
+        holder: &mut Character,
+        style: MartialArtsStyle,
+        luck: i32,
+        roller: &mut dyn DieRoller,
+    ) -> Result<OpposedResult, String> {
+        self.check_sides(holder, target)?;
+        if self.stage == GrappleStage::Released {
+            return Err(self.over());
+        }
+        let escape_spec = skill_or_reflexes(target, style.skill());
+        let hold_spec = skill_or_reflexes(holder, self.style.skill());
+        target.check_values(&escape_spec)?;
+        target.check_luck(luck)?;
+        holder.check_values(&hold_spec)?;
+
+        let mut escape = Situation::new();
+        if style.key_attack_bonus(MartialAction::Escape) != 0 {
+            escape = escape.with_modifier(style.key_attack_modifier(MartialAction::Escape));
+        }
+        let mut hold = Situation::new();
+        if self.stage != GrappleStage::Grappled
+            && self.style.key_attack_bonus(MartialAction::Hold) != 0
+        {
+            hold = hold.with_modifier(self.style.key_attack_modifier(MartialAction::Hold));
+        }
+        if self.rounds_held > 0 {
+            hold = hold.with_bonus("rounds held", HOLD_BONUS_PER_ROUND * self.rounds_held);
+        }
+        let escape_roll = target.open_check_with(&escape_spec, luck, &escape, roller)?;
+        let hold_roll = holder.open_check_with(&hold_spec, 0, &hold, roller)?;
+        let result = resolve_opposed(escape_roll, hold_roll);
+        if result.winner == OpposedWinner::First {
+            self.stage = GrappleStage::Released;
+        }
+        Ok(result)
+    }
+
+    /// Ends a round of the grapple: a hold that is still up counts one more
+    /// round, and a choke deals its damage (1d6 plus the holder's skill
+    /// level) to the held zone via [`Character::take_damage`].
+    pub fn next_round(
+        &mut self,
+        holder: &Character,
+        target: &mut Character,
+        roller: &mut dyn DieRoller,
+    ) -> Result<Option<MeleeHit>, String> {
+        self.check_sides(holder, target)?;
+        match self.stage {
+            GrappleStage::Held => {
+                self.rounds_held += 1;
+                Ok(None)
+            }
+            GrappleStage::Choking => {
+                self.rounds_held += 1;
+                Ok(Some(choke(holder, target, self.style, self.zone, roller)?))
+            }
+            GrappleStage::Grappled | GrappleStage::Released => Ok(None),
+        }
+    }
+
+    /// Whether the grapple is still on.
+    pub fn is_active(&self) -> bool {
+        self.stage != GrappleStage::Released
+    }
+
+    fn roll(
+        &self,
+        holder: &mut Character,
+        target: &mut Character,
+        action: MartialAction,
+        roller: &mut dyn DieRoller,
+    ) -> Result<MartialArtsReport, String> {
+        let attack = MartialArtsAttack::new(self.style, action, MeleeDefense::None)
+            .with_rounds_held(self.rounds_held)
+            .with_zone(self.zone);
+        martial_arts_attack(holder, target, &attack, roller)
+    }
+
+    fn follow_up(
+        &self,
+        holder: &Character,
+        target: &Character,
+        action: MartialAction,
+        allowed: &[GrappleStage],
+    ) -> Result<(), String> {
+        self.check_sides(holder, target)?;
+        if self.stage == GrappleStage::Released {
+            return Err(self.over());
+        }
+        if !allowed.contains(&self.stage) {
+            return Err(format!("{} can't follow in stage {}", action, self.stage));
+        }
+        Ok(())
+    }
+
+    fn check_sides(&self, holder: &Character, target: &Character) -> Result<(), String> {
+        if holder.name != self.holder || target.name != self.target {
+            return Err(format!(
+                "The grapple is between '{}' (holder) and '{}' (target)",
+                self.holder, self.target
+            ));
+        }
+        Ok(())
+    }
+
+    fn over(&self) -> String {
+        format!(
+            "The grapple between '{}' and '{}' is over",
+            self.holder, self.target
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::tests::solo;
+    use crate::character::{Attribute, Skill};
+    use crate::dice::SequenceRoller;
+    use crate::health::WoundState;
+
+    fn fighter(name: &str, ringen: i32) -> Character {
+        let mut character = solo(name);
+        if ringen > 0 {
+            character.skills.push(Skill::new(
+                "Ringen".to_string(),
+                Attribute::Reflexes,
+                ringen,
+                1,
+            ));
+        }
+        character
+    }
+
+    fn grappled(wrestler: &mut Character, target: &mut Character) -> Grapple {
+        // 8 + 3 + 4 + 5 = 20 vs 8 + 5 = 13
+        let mut roller = SequenceRoller::new(vec![5, 5]);
+        let (report, grapple) = Grapple::attempt(
+            wrestler,
+            target,
+            MartialArtsStyle::Ringen,
+            MeleeDefense::Dodge,
+            &mut roller,
+        )
+        .unwrap();
+        assert!(report.succeeded());
+        grapple.unwrap()
+    }
+
+    #[test]
+    fn test_hold_choke_and_escape() {
+        let mut wrestler = fighter("Wrestler", 3);
+        let mut target = fighter("Target", 2);
+        let mut grapple = grappled(&mut wrestler, &mut target);
+        assert_eq!(grapple.stage, GrappleStage::Grappled);
+
+        // 8 + 3 + 4 beats the easy check without a roll
+        let mut roller = SequenceRoller::new(vec![]);
+        let report = grapple
+            .hold(&mut wrestler, &mut target, HitZone::Head, &mut roller)
+            .unwrap();
+        assert!(report.succeeded());
+        assert_eq!(
+            (grapple.stage, grapple.zone),
+            (GrappleStage::Held, HitZone::Head)
+        );
+        let mut roller = SequenceRoller::new(vec![]);
+        for _ in 0..2 {
+            let hit = grapple
+                .next_round(&wrestler, &mut target, &mut roller)
+                .unwrap();
+            assert_eq!(hit, None);
+        }
+        assert_eq!(grapple.rounds_held, 2);
+
+        // 1d6 = 4, + Ringen 3 on the held head
+        let mut roller = SequenceRoller::new(vec![4]);
+        let report = grapple
+            .choke(&mut wrestler, &mut target, &mut roller)
+            .unwrap();
+        assert_eq!(grapple.stage, GrappleStage::Choking);
+        let hit = report.hit.unwrap();
+        assert_eq!((hit.zone, hit.damage), (HitZone::Head, 7));
+        let mut roller = SequenceRoller::new(vec![2]);
+        let hit = grapple
+            .next_round(&wrestler, &mut target, &mut roller)
+            .unwrap()
+            .unwrap();
+        assert_eq!((hit.zone, hit.damage, hit.dam), (HitZone::Head, 5, 0));
+        assert_eq!(grapple.rounds_held, 3);
+
+        // Critically wounded by now: REF 8 - 4 + 2 + 4 + 10 + 3 = 23
+        // vs 8 + 3 + 4 + 3 rounds + 4 = 22
+        assert_eq!(target.wound_state(), WoundState::Critical);
+        let mut roller = SequenceRoller::new(vec![10, 3, 4]);
+        let result = grapple
+            .escape(
+                &mut target,
+                &mut wrestler,
+                MartialArtsStyle::Ringen,
+                0,
+                &mut roller,
+            )
+            .unwrap();
+        assert_eq!(result.winner, OpposedWinner::First);
+        assert!(!grapple.is_active());
+        let mut roller = SequenceRoller::new(vec![]);
+        assert_eq!(
+            grapple
+                .next_round(&wrestler, &mut target, &mut roller)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            grapple
+                .throw(&mut wrestler, &mut target, &mut roller)
+                .unwrap_err(),
+            "The grapple between 'Wrestler' and 'Target' is over"
+        );
+    }
+
+    #[test]
+    fn test_follow_ups_are_enforced() {
+        let mut wrestler = fighter("Wrestler", 3);
+        let mut target = fighter("Target", 0);
+        let mut grapple = grappled(&mut wrestler, &mut target);
+        let mut roller = SequenceRoller::new(vec![]);
+        assert_eq!(
+            grapple
+                .choke(&mut wrestler, &mut target, &mut roller)
+                .unwrap_err(),
+            "Choke can't follow in stage Grappled"
+        );
+        assert_eq!(
+            grapple
+                .hold(&mut target, &mut wrestler, HitZone::Chest, &mut roller)
+                .unwrap_err(),
+            "The grapple is between 'Wrestler' (holder) and 'Target' (target)"
+        );
+
+        // Bare REF: 8 + 6 = 14 vs 8 + 3 + 3 = 14, the holder keeps the grip
+        let mut roller = SequenceRoller::new(vec![6, 3]);
+        let result = grapple
+            .escape(
+                &mut target,
+                &mut wrestler,
+                MartialArtsStyle::Pruegeln,
+                0,
+                &mut roller,
+            )
+            .unwrap();
+        assert_eq!(result.winner, OpposedWinner::Tie);
+        assert!(grapple.is_active());
+
+        // Easy check: 8 + 3 + 3 (Ringen Throw) needs no die;
+        // hit location, 1d6 and the stun check follow
+        let mut roller = SequenceRoller::new(vec![5, 2, 5]);
+        let report = grapple
+            .throw(&mut wrestler, &mut target, &mut roller)
+            .unwrap();
+        assert!(report.stun_check.is_some());
+        assert_eq!(grapple.stage, GrappleStage::Released);
+    }
+}
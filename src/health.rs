@@ -1,4 +1,5 @@
 use crate::character::Attribute;
+use std::fmt;
 
 /// Wound state per the house rules (Regeln → Gesundheit): health is divided
 /// into blocks of four hit points.
@@ -23,6 +24,12 @@ pub enum WoundState {
     Dead,
 }
 
+impl fmt::Display for WoundState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 impl WoundState {
     pub fn from_damage(damage: i32) -> Self {
         match damage {
@@ -0,0 +1,333 @@
+use crate::armor::HitZone;
+use crate::dice::{CheckModifier, DieRoller, ModifierSource};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// Called shot on a zone (Regeln → Trefferzonen ausnutzen). Damage to the
+/// head doubles as always.
+pub const CALLED_SHOT_MALUS: i32 = 4;
+
+/// The to-hit malus of a called shot on `zone`.
+pub fn called_shot_modifier(zone: HitZone) -> CheckModifier {
+    CheckModifier::new(
+        ModifierSource::Situational(format!("called shot {}", zone)),
+        -CALLED_SHOT_MALUS,
+    )
+}
+
+/// Rolls the hit location on the CP2020 d10 table, see
+/// [`HitLocationTable::cp2020`].
+pub fn roll_hit_zone(roller: &mut dyn DieRoller) -> HitZone {
+    HitLocationTable::cp2020().roll(roller)
+}
+
+/// The die results `from..=to` hit `zone`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HitLocationRange {
+    pub from: i32,
+    pub to: i32,
+    pub zone: HitZone,
+}
+
+impl HitLocationRange {
+    pub fn new(from: i32, to: i32, zone: HitZone) -> Self {
+        HitLocationRange { from, to, zone }
+    }
+}
+
+/// Which [`HitZone`] a random hit lands on: a die and the zone each of its
+/// results maps to. Every result of the die maps to exactly one zone.
+///
+/// [`HitLocationTable::cp2020`] is the default; [`HitLocationTable::detailed`]
+/// spreads a d100 over all 14 zones the armor knows. Other tables are read
+/// from TOML, `die = 6` followed by one `[[range]]` table (`from`, `to`,
+/// `zone`) per zone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedHitLocationTable")]
+pub struct HitLocationTable {
+    die: i32,
+    #[serde(rename = "range")]
+    ranges: Vec<HitLocationRange>,
+}
+
+/// Mirror of `HitLocationTable` without validation, used as the
+/// deserialization input: tables can only be produced through
+/// [`HitLocationTable::new`], which rejects gaps, overlaps and ranges
+/// outside the die.
+#[derive(Deserialize)]
+struct UncheckedHitLocationTable {
+    die: i32,
+    #[serde(rename = "range")]
+    ranges: Vec<HitLocationRange>,
+}
+
+impl TryFrom<UncheckedHitLocationTable> for HitLocationTable {
+    type Error = String;
+
+    fn try_from(raw: UncheckedHitLocationTable) -> Result<Self, Self::Error> {
+        HitLocationTable::new(raw.die, raw.ranges)
+    }
+}
+
+impl HitLocationTable {
+    pub fn new(die: i32, ranges: Vec<HitLocationRange>) -> Result<Self, String> {
+        if die < 2 {
+            return Err(format!("A hit location table needs a die, not d{}", die));
+        }
+        for result in 1..=die {
+            let zones: Vec<HitZone> = ranges
+                .iter()
+                .filter(|range| (range.from..=range.to).contains(&result))
+                .map(|range| range.zone)
+                .collect();
+            match zones.len() {
+                0 => {
+                    return Err(format!(
+                        "Hit location table leaves {} on the d{} without a zone",
+                        result, die
+                    ))
+                }
+                1 => {}
+                _ => {
+                    return Err(format!(
+                        "Hit location table maps {} on the d{} to {} zones",
+                        result,
+                        die,
+                        zones.len()
+                    ))
+                }
+            }
+        }
+        if let Some(range) = ranges.iter().find(|range| range.from < 1 || range.to > die) {
+            return Err(format!(
+                "Hit location table maps {}-{} to {}, outside the d{}",
+                range.from, range.to, range.zone, die
+            ));
+        }
+        Ok(HitLocationTable { die, ranges })
+    }
+
+    /// The CP2020 d10 table: 1 head, 2–4 torso, 5 right arm, 6 left arm,
+    /// 7–8 right leg, 9–10 left leg. The torso is split onto chest (2–3)
+    /// and stomach (4).
+    pub fn cp2020() -> Self {
+        Self::from_ranges(
+            10,
+            &[
+                (1, 1, HitZone::Head),
+                (2, 3, HitZone::Chest),
+                (4, 4, HitZone::Stomach),
+                (5, 5, HitZone::RightArm),
+                (6, 6, HitZone::LeftArm),
+                (7, 8, HitZone::RightLeg),
+                (9, 10, HitZone::LeftLeg),
+            ],
+        )
+    }
+
+    /// A d100 table over all 14 zones, keeping the CP2020 odds per body
+    /// part (Q38): head 10 %, torso 30 % (shoulders, chest, stomach,
+    /// vitals), 10 % per arm with the hand and 40 % for the legs (thighs,
+    /// both legs and feet).
+    pub fn detailed() -> Self {
+        Self::from_ranges(
+            100,
+            &[
+                (1, 10, HitZone::Head),
+                (11, 16, HitZone::Shoulders),
+                (17, 30, HitZone::Chest),
+                (31, 36, HitZone::Stomach),
+                (37, 40, HitZone::Vitals),
+                (41, 47, HitZone::RightArm),
+                (48, 50, HitZone::RightHand),
+                (51, 57, HitZone::LeftArm),
+                (58, 60, HitZone::LeftHand),
+                (61, 70, HitZone::Thighs),
+                (71, 82, HitZone::RightLeg),
+                (83, 85, HitZone::RightFoot),
+                (86, 97, HitZone::LeftLeg),
+                (98, 100, HitZone::LeftFoot),
+            ],
+        )
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|error| format!("Invalid hit location table: {}", error))
+    }
+
+    fn from_ranges(die: i32, ranges: &[(i32, i32, HitZone)]) -> Self {
+        let ranges = ranges
+            .iter()
+            .map(|&(from, to, zone)| HitLocationRange::new(from, to, zone))
+            .collect();
+        Self::new(die, ranges)
+            .unwrap_or_else(|error| panic!("Broken built-in hit location table: {}", error))
+    }
+
+    /// The sides of the die this table is rolled with.
+    pub fn die(&self) -> i32 {
+        self.die
+    }
+
+    pub fn ranges(&self) -> &[HitLocationRange] {
+        &self.ranges
+    }
+
+    /// The zone a die result maps to, `None` outside the die.
+    pub fn zone(&self, result: i32) -> Option<HitZone> {
+        self.ranges
+            .iter()
+            .find(|range| (range.from..=range.to).contains(&result))
+            .map(|range| range.zone)
+    }
+
+    pub fn roll(&self, roller: &mut dyn DieRoller) -> HitZone {
+        let result = roller.roll(self.die);
+        self.zone(result)
+            .unwrap_or_else(|| panic!("d{} rolled {}", self.die, result))
+    }
+
+    /// Where a hit on `target` lands: the zone of a called shot, otherwise
+    /// one rolled on this table.
+    pub fn locate(
+        &self,
+        called_shot: Option<HitZone>,
+        target: &str,
+        roller: &mut dyn DieRoller,
+    ) -> HitZone {
+        match called_shot {
+            Some(zone) => zone,
+            None => {
+                roller.annotate(&format!("{}: hit location", target));
+                self.roll(roller)
+            }
+        }
+    }
+}
+
+impl Default for HitLocationTable {
+    fn default() -> Self {
+        Self::cp2020()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice::SequenceRoller;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_cp2020_table() {
+        let table = HitLocationTable::cp2020();
+        let zones: Vec<HitZone> = (1..=10).map(|result| table.zone(result).unwrap()).collect();
+        assert_eq!(
+            zones,
+            vec![
+                HitZone::Head,
+                HitZone::Chest,
+                HitZone::Chest,
+                HitZone::Stomach,
+                HitZone::RightArm,
+                HitZone::LeftArm,
+                HitZone::RightLeg,
+                HitZone::RightLeg,
+                HitZone::LeftLeg,
+                HitZone::LeftLeg,
+            ]
+        );
+        assert_eq!(table.zone(11), None);
+        assert_eq!(HitLocationTable::default(), table);
+    }
+
+    #[test]
+    fn test_detailed_table_covers_every_zone() {
+        let table = HitLocationTable::detailed();
+        assert!((1..=100).all(|result| table.zone(result).is_some()));
+        let zones: BTreeSet<HitZone> = (1..=100).filter_map(|result| table.zone(result)).collect();
+        let every_zone: BTreeSet<HitZone> = HitZone::ALL.iter().copied().collect();
+        assert_eq!(zones, every_zone);
+        let mut roller = SequenceRoller::new(vec![38, 99]);
+        assert_eq!(table.roll(&mut roller), HitZone::Vitals);
+        assert_eq!(table.roll(&mut roller), HitZone::LeftFoot);
+    }
+
+    #[test]
+    fn test_called_shots_skip_the_roll() {
+        let table = HitLocationTable::detailed();
+        let mut roller = SequenceRoller::new(vec![]);
+        assert_eq!(
+            table.locate(Some(HitZone::RightHand), "Target", &mut roller),
+            HitZone::RightHand
+        );
+        let modifier = called_shot_modifier(HitZone::RightHand);
+        assert_eq!(modifier.to_string(), "-4 called shot RightHand");
+    }
+
+    #[test]
+    fn test_tables_from_toml() {
+        let table = HitLocationTable::from_toml(
+            r#"
+            die = 6
+            [[range]]
+            from = 1
+            to = 2
+            zone = "Head"
+            [[range]]
+            from = 3
+            to = 6
+            zone = "Chest"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(table.die(), 6);
+        assert_eq!(table.zone(2), Some(HitZone::Head));
+
+        let gap = vec![
+            HitLocationRange::new(1, 2, HitZone::Head),
+            HitLocationRange::new(4, 6, HitZone::Chest),
+        ];
+        assert_eq!(
+            HitLocationTable::new(6, gap).unwrap_err(),
+            "Hit location table leaves 3 on the d6 without a zone"
+        );
+        let overlap = vec![
+            HitLocationRange::new(1, 3, HitZone::Head),
+            HitLocationRange::new(3, 6, HitZone::Chest),
+        ];
+        assert_eq!(
+            HitLocationTable::new(6, overlap).unwrap_err(),
+            "Hit location table maps 3 on the d6 to 2 zones"
+        );
+        let outside = vec![
+            HitLocationRange::new(1, 2, HitZone::Head),
+            HitLocationRange::new(3, 8, HitZone::Chest),
+        ];
+        assert_eq!(
+            HitLocationTable::new(6, outside).unwrap_err(),
+            "Hit location table maps 3-8 to Chest, outside the d6"
+        );
+    }
+
+    #[test]
+    fn test_invalid_table_fails_to_deserialize() {
+        let gap = r#"
+            die = 6
+            [[range]]
+            from = 1
+            to = 2
+            zone = "Head"
+            "#;
+        let error = toml::from_str::<HitLocationTable>(gap).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Hit location table leaves 3 on the d6 without a zone"));
+        assert!(HitLocationTable::from_toml(gap)
+            .unwrap_err()
+            .starts_with("Invalid hit location table: Hit location table leaves 3"));
+
+        let table = HitLocationTable::detailed();
+        let text = toml::to_string(&table).unwrap();
+        assert_eq!(HitLocationTable::from_toml(&text), Ok(table));
+    }
+}
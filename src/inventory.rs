@@ -1,4 +1,5 @@
 use crate::armor::Armor;
+use crate::weapons::{Ammunition, Weapon};
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt;
@@ -38,9 +39,13 @@ struct SerializeableInventory {
 
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "type", content = "data")]
+// the variant names are the `type` tags in saved files
+#[allow(clippy::enum_variant_names)]
 enum SerializeableInventoryItem {
     BasicItem(Item),
     ArmorItem(Armor),
+    WeaponItem(Weapon),
+    AmmunitionItem(Ammunition),
 }
 
 impl PartialEq for Inventory {
@@ -66,6 +71,12 @@ impl Serialize for Inventory {
         for item in &self.items {
             if let Some(armor) = item.as_any().downcast_ref::<Armor>() {
                 serializable_items.push(SerializeableInventoryItem::ArmorItem(armor.clone()));
+            } else if let Some(weapon) = item.as_any().downcast_ref::<Weapon>() {
+                serializable_items.push(SerializeableInventoryItem::WeaponItem(weapon.clone()));
+            } else if let Some(ammunition) = item.as_any().downcast_ref::<Ammunition>() {
+                serializable_items.push(SerializeableInventoryItem::AmmunitionItem(
+                    ammunition.clone(),
+                ));
             } else if let Some(basic) = item.as_any().downcast_ref::<Item>() {
                 serializable_items.push(SerializeableInventoryItem::BasicItem(basic.clone()));
             } else {
@@ -94,6 +105,10 @@ impl<'de> Deserialize<'de> for Inventory {
             match item {
                 SerializeableInventoryItem::BasicItem(basic) => items.push(Box::new(basic)),
                 SerializeableInventoryItem::ArmorItem(armor) => items.push(Box::new(armor)),
+                SerializeableInventoryItem::WeaponItem(weapon) => items.push(Box::new(weapon)),
+                SerializeableInventoryItem::AmmunitionItem(ammunition) => {
+                    items.push(Box::new(ammunition))
+                }
             }
         }
 
@@ -205,6 +220,20 @@ impl Inventory {
             .collect()
     }
 
+    pub fn get_all_weapons(&self) -> Vec<&Weapon> {
+        self.items
+            .iter()
+            .filter_map(|item| item.as_any().downcast_ref::<Weapon>())
+            .collect()
+    }
+
+    pub fn get_all_ammunition(&self) -> Vec<&Ammunition> {
+        self.items
+            .iter()
+            .filter_map(|item| item.as_any().downcast_ref::<Ammunition>())
+            .collect()
+    }
+
     pub fn get_item(&self, uuid: Uuid) -> Option<&dyn InventoryItem> {
         self.items
             .iter()
@@ -230,6 +259,15 @@ impl Inventory {
     pub fn push(&mut self, item: Box<dyn InventoryItem>) {
         self.items.push(item);
     }
+
+    /// Takes the item out of the inventory.
+    pub fn remove(&mut self, uuid: Uuid) -> Option<Box<dyn InventoryItem>> {
+        let index = self
+            .items
+            .iter()
+            .position(|item| item.get_item().uuid == uuid)?;
+        Some(self.items.remove(index))
+    }
 }
 
 impl Item {
@@ -364,6 +402,29 @@ mod tests {
         assert_eq!(inv, deserialized);
     }
 
+    #[test]
+    fn test_inventory_serialization_with_armor_weapons_and_ammunition() {
+        use crate::weapons::tests::ak_47;
+        let mut inv = create_simple_inventory_with_armor();
+        inv.push(Box::new(ak_47()));
+
+        inv.push(Box::new(Ammunition::new(
+            "7.62 AP".to_string(),
+            "7.62x39".to_string(),
+            crate::DamageType::ArmorPiercing,
+            57,
+            16,
+            2,
+        )));
+
+        let serialized = toml::to_string(&inv).unwrap();
+        let deserialized: Inventory = toml::from_str(&serialized).unwrap();
+        assert_eq!(inv, deserialized);
+        assert_eq!(deserialized.get_all_weapons(), inv.get_all_weapons());
+        assert_eq!(deserialized.get_all_ammunition()[0].count(), 57);
+        assert_eq!(deserialized.get_all_armor().len(), 2);
+    }
+
     #[test]
     fn test_calc_weight() {
         let inv = create_simple_inventory_with_armor();
@@ -1,9 +1,19 @@
 mod advantages;
 mod armor;
 mod character;
+mod combat;
 mod dice;
+mod encounter;
+mod grapple;
 mod health;
+mod hit_location;
 mod inventory;
+mod martial_arts;
+mod melee;
+mod opposed;
+mod simulation;
+mod skill_tree;
+mod team;
 mod weapons;
 
 pub use self::advantages::{
@@ -11,11 +21,45 @@ pub use self::advantages::{
     TAG_HEALING_RATE, TAG_INITIATIVE,
 };
 pub use self::armor::{Armor, HitZone};
-pub use self::character::{Attribute, AttributeValue, Character, HitOutcome, List, Skill};
-pub use self::dice::{open_roll, skill_check};
+pub use self::character::{
+    Attribute, AttributeValue, Character, CheckSpec, HitOutcome, List, Skill,
+};
+pub use self::combat::{
+    malfunction_check, ranged_attack, AimingAid, AimingState, AttackReport, BulletHit, FireMode,
+    RangeBracket, RangedAttack, MOTIONLESS_TARGET_BONUS, UNFAMILIAR_WEAPON_SHIFT,
+    WEAPON_FAMILIARITY_USES,
+};
+pub use self::dice::{open_roll, skill_check, skill_check_probabilities};
 pub use self::dice::{
-    CheckResult, DieRoller, Difficulty, OpenRollResult, Outcome, RandomRoller, SequenceRoller,
+    CheckModifier, CheckProbabilities, CheckResult, DiceExpression, DiceRoll, DieRoller,
+    Difficulty, ModifierSource, OpenRollResult, Outcome, RandomRoller, RecordedRoll,
+    RecordingRoller, ReplayRoller, RollLog, SeededRoller, SequenceRoller, Situation,
 };
+pub use self::encounter::{
+    CombatStatus, Combatant, CombatantKind, Encounter, QUICK_DRAW_ACTION_MALUS,
+    QUICK_DRAW_INITIATIVE_BONUS,
+};
+pub use self::grapple::{Grapple, GrappleStage};
 pub use self::health::WoundState;
+pub use self::hit_location::{
+    called_shot_modifier, roll_hit_zone, HitLocationRange, HitLocationTable, CALLED_SHOT_MALUS,
+};
 pub use self::inventory::{Inventory, Item};
-pub use self::weapons::DamageType;
+pub use self::martial_arts::{
+    martial_arts_attack, MartialAction, MartialArtsAttack, MartialArtsReport, MartialArtsStyle,
+    HOLD_BONUS_PER_ROUND, THROW_STUN_MALUS,
+};
+pub use self::melee::{
+    melee_attack, MeleeAttack, MeleeDefense, MeleeHit, MeleeReport, MeleeRoll, DODGE_SKILL,
+};
+pub use self::opposed::{opposed_check, resolve_opposed, OpposedResult, OpposedWinner};
+pub use self::simulation::{simulate_attacks, simulate_checks, CheckStats, HitStats};
+pub use self::skill_tree::{SkillTree, MELEE_SKILLS, SKILL_TREES};
+pub use self::team::{
+    skill_pool, supported_check, teach, Participant, PoolMember, PoolResult, PoolSplit,
+    SupportedResult, TeachingResult, SKILL_TEACHING, SUPPORTER_MINIMUM,
+};
+pub use self::weapons::{
+    Ammunition, Availability, CatalogEntry, Concealability, DamageType, LoadedRounds, Malfunction,
+    MeleeClass, Reliability, Weapon, WeaponCatalog, WeaponCategory,
+};
@@ -0,0 +1,515 @@
+use crate::armor::HitZone;
+use crate::character::{Character, CheckSpec};
+use crate::dice::{
+    CheckModifier, CheckResult, DiceExpression, DieRoller, ModifierSource, Situation,
+};
+use crate::hit_location::{called_shot_modifier, HitLocationTable};
+use crate::melee::{land_blow, roll_melee, MeleeDefense, MeleeHit, MeleeRoll};
+use crate::weapons::DamageType;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Malus on the stun (KO) check of a thrown character.
+pub const THROW_STUN_MALUS: i32 = 2;
+/// Bonus on a Hold for every round it has already been kept up.
+pub const HOLD_BONUS_PER_ROUND: i32 = 1;
+
+/// The martial arts styles of the table (Q26), each rolled on the skill of
+/// the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MartialArtsStyle {
+    /// Brawling: no key attacks.
+    Pruegeln,
+    Boxen,
+    Ringen,
+}
+
+impl MartialArtsStyle {
+    pub fn skill(self) -> &'static str {
+        match self {
+            MartialArtsStyle::Pruegeln => "Prügeln",
+            MartialArtsStyle::Boxen => "Boxen",
+            MartialArtsStyle::Ringen => "Ringen",
+        }
+    }
+
+    /// The style's key-attack bonus for `action`, added to the roll.
+    pub fn key_attack_bonus(self, action: MartialAction) -> i32 {
+        use MartialAction::*;
+        match (self, action) {
+            (MartialArtsStyle::Boxen, Strike) | (MartialArtsStyle::Boxen, Sweep) => 3,
+            (MartialArtsStyle::Boxen, Block) => 1,
+            (MartialArtsStyle::Ringen, Sweep) | (MartialArtsStyle::Ringen, Choke) => 2,
+            (MartialArtsStyle::Ringen, Throw) => 3,
+            (MartialArtsStyle::Ringen, Grapple)
+            | (MartialArtsStyle::Ringen, Hold)
+            | (MartialArtsStyle::Ringen, Escape) => 4,
+            _ => 0,
+        }
+    }
+
+    /// [`MartialArtsStyle::key_attack_bonus`] as an itemized modifier.
+    pub fn key_attack_modifier(self, action: MartialAction) -> CheckModifier {
+        CheckModifier::new(
+            ModifierSource::Situational(format!("{} {}", self, action)),
+            self.key_attack_bonus(action),
+        )
+    }
+}
+
+impl fmt::Display for MartialArtsStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// The hand-to-hand actions every style can use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MartialAction {
+    /// Strike or punch: 1d3 + DAM.
+    Strike,
+    /// 1d6 + DAM.
+    Kick,
+    /// Knocks the target down.
+    Sweep,
+    /// Defense: see [`MeleeDefense::Block`].
+    Block,
+    /// Defense: see [`MeleeDefense::Dodge`].
+    Dodge,
+    /// Knocks the weapon out of the target's hand.
+    Disarm,
+    /// Gets hold of the target.
+    Grapple,
+    /// 1d6 + DAM, and the target rolls a stun check at −2.
+    Throw,
+    /// Immobilizes a grappled target; +1 for every round already held.
+    Hold,
+    /// Breaks free of a grapple or hold.
+    Escape,
+    /// 1d6 per round on a held target.
+    Choke,
+}
+
+impl MartialAction {
+    /// The damage dice of the action, before DAM and skill level; `None`
+    /// for actions that deal no damage.
+    pub fn damage(self) -> Option<DiceExpression> {
+        match self {
+            MartialAction::Strike => Some(DiceExpression::new(1, 3, 0)),
+            MartialAction::Kick | MartialAction::Throw | MartialAction::Choke => {
+                Some(DiceExpression::new(1, 6, 0))
+            }
+            _ => None,
+        }
+    }
+
+    /// Block and Dodge answer an attack instead of being one.
+    pub fn is_defense(self) -> bool {
+        matches!(self, MartialAction::Block | MartialAction::Dodge)
+    }
+}
+
+impl fmt::Display for MartialAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A martial arts action against a defender. Situation, rounds the hold has
+/// been kept up, the zone a Choke damages, called shot and hit location
+/// table of a Strike, Kick or Throw and committed luck are set with the
+/// `with_*` methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MartialArtsAttack {
+    pub style: MartialArtsStyle,
+    pub action: MartialAction,
+    pub defense: MeleeDefense,
+    pub situation: Situation,
+    /// Rounds a Hold has already been kept up.
+    pub rounds_held: i32,
+    /// The held zone a Choke damages.
+    pub zone: HitZone,
+    pub called_shot: Option<HitZone>,
+    /// The table random blows are located on, CP2020 by default.
+    pub hit_locations: HitLocationTable,
+    pub luck: i32,
+}
+
+impl MartialArtsAttack {
+    pub fn new(style: MartialArtsStyle, action: MartialAction, defense: MeleeDefense) -> Self {
+        MartialArtsAttack {
+            style,
+            action,
+            defense,
+            situation: Situation::new(),
+            rounds_held: 0,
+            zone: HitZone::Chest,
+            called_shot: None,
+            hit_locations: HitLocationTable::default(),
+            luck: 0,
+        }
+    }
+
+    pub fn with_situation(mut self, situation: Situation) -> Self {
+        self.situation = situation;
+        self
+    }
+
+    pub fn with_rounds_held(mut self, rounds: i32) -> Self {
+        self.rounds_held = rounds;
+        self
+    }
+
+    pub fn with_zone(mut self, zone: HitZone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// Aims a Strike, Kick or Throw at `zone` for
+    /// [`CALLED_SHOT_MALUS`](crate::CALLED_SHOT_MALUS).
+    pub fn with_called_shot(mut self, zone: HitZone) -> Self {
+        self.called_shot = Some(zone);
+        self
+    }
+
+    pub fn with_hit_locations(mut self, table: HitLocationTable) -> Self {
+        self.hit_locations = table;
+        self
+    }
+
+    pub fn with_luck(mut self, luck: i32) -> Self {
+        self.luck = luck;
+        self
+    }
+}
+
+/// Everything a martial arts action did.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MartialArtsReport {
+    pub style: MartialArtsStyle,
+    pub action: MartialAction,
+    pub roll: MeleeRoll,
+    /// Damage of a landed Strike, Kick, Throw or Choke.
+    pub hit: Option<MeleeHit>,
+    /// The defender's stun check after a landed Throw.
+    pub stun_check: Option<CheckResult>,
+}
+
+impl MartialArtsReport {
+    /// Whether the action worked: the blow landed, the target went down,
+    /// was disarmed, grappled or held, or the attacker broke free.
+    pub fn succeeded(&self) -> bool {
+        self.roll.is_hit()
+    }
+}
+
+/// Resolves a martial arts action of `attacker` against `defender`.
+///
+/// The attacker needs the style's skill — even for Prügeln; brawling
+/// untrained is a deliberate [`Character::check_attribute`] on REF — and
+/// rolls on it plus its key-attack bonus for
+/// the action (and, for a Hold, +1 per round already held) against the
+/// defense, like a melee attack. Damaging actions deal their dice plus the
+/// style's skill level (Q26) — and DAM, except for a Choke — to the called
+/// zone or a location rolled on the attack's table; a Choke goes to the
+/// held zone (the chest unless set) and ignores armor (Q36). A thrown
+/// defender rolls a stun check at −[`THROW_STUN_MALUS`]. Sweep, Disarm,
+/// Grapple, Hold and Escape only report whether they worked;
+/// [`Grapple`](crate::Grapple) keeps track of grapples and holds across
+/// rounds.
+pub fn martial_arts_attack(
+    attacker: &mut Character,
+    defender: &mut Character,
+    attack: &MartialArtsAttack,
+    roller: &mut dyn DieRoller,
+) -> Result<MartialArtsReport, String> {
+    if attack.action.is_defense() {
+        return Err(format!(
+            "{} is a defense, declare it as the defender's MeleeDefense",
+            attack.action
+        ));
+    }
+    let aimable = matches!(
+        attack.action,
+        MartialAction::Strike | MartialAction::Kick | MartialAction::Throw
+    );
+    if attack.called_shot.is_some() && !aimable {
+        return Err(format!(
+            "A {} can't be a called shot, only a Strike, Kick or Throw",
+            attack.action
+        ));
+    }
+    let skill = attack.style.skill();
+    let (_, level) = attacker.trained_skill(skill)?;
+    let mut situation = attack.situation.clone();
+    if attack.style.key_attack_bonus(attack.action) != 0 {
+        situation = situation.with_modifier(attack.style.key_attack_modifier(attack.action));
+    }
+    if attack.action == MartialAction::Hold && attack.rounds_held > 0 {
+        situation = situation.with_bonus("rounds held", HOLD_BONUS_PER_ROUND * attack.rounds_held);
+    }
+    if let Some(zone) = attack.called_shot {
+        situation = situation.with_modifier(called_shot_modifier(zone));
+    }
+    let roll = roll_melee(
+        attacker,
+        &CheckSpec::Skill(skill.to_string()),
+        attack.luck,
+        &situation,
+        defender,
+        attack.defense,
+        roller,
+    )?;
+
+    let mut hit = None;
+    let mut stun_check = None;
+    if let (true, Some(dice)) = (roll.is_hit(), attack.action.damage()) {
+        let source = format!("{} {}", attacker.name, attack.action);
+        if attack.action == MartialAction::Choke {
+            hit = Some(choke(
+                attacker,
+                defender,
+                attack.style,
+                attack.zone,
+                roller,
+            )?);
+        } else {
+            let zone = attack
+                .hit_locations
+                .locate(attack.called_shot, &defender.name, roller);
+            let dice = DiceExpression {
+                modifier: dice.modifier + level,
+                ..dice
+            };
+            hit = Some(land_blow(
+                attacker,
+                defender,
+                zone,
+                &dice,
+                DamageType::Blunt,
+                &source,
+                roller,
+            ));
+        }
+        if attack.action == MartialAction::Throw {
+            let situation = Situation::new().with_bonus("thrown", -THROW_STUN_MALUS);
+            stun_check = Some(defender.ko_check_with(&situation, roller));
+        }
+    }
+    Ok(MartialArtsReport {
+        style: attack.style,
+        action: attack.action,
+        roll,
+        hit,
+        stun_check,
+    })
+}
+
+/// One round of choking: 1d6 plus the holder's skill level in `style`,
+/// straight onto `zone` of the held character — no armor, no DAM. The
+/// holder needs the style's skill.
+pub(crate) fn choke(
+    holder: &Character,
+    held: &mut Character,
+    style: MartialArtsStyle,
+    zone: HitZone,
+    roller: &mut dyn DieRoller,
+) -> Result<MeleeHit, String> {
+    let (_, level) = holder.trained_skill(style.skill())?;
+    roller.annotate(&format!("{} {}: damage", holder.name, MartialAction::Choke));
+    let dice = MartialAction::Choke.damage().expect("a choke deals damage");
+    let damage = dice.roll(roller).total + level;
+    let outcome = held.take_damage(damage, zone);
+    Ok(MeleeHit {
+        zone,
+        damage,
+        dam: 0,
+        outcome,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::tests::solo;
+    use crate::character::{Attribute, Skill};
+    use crate::dice::SequenceRoller;
+    use crate::melee::{melee_attack, MeleeAttack};
+    use crate::weapons::WeaponCatalog;
+
+    fn fighter(name: &str, style: Option<(MartialArtsStyle, i32)>) -> Character {
+        let mut character = solo(name);
+        if let Some((style, level)) = style {
+            character.skills.push(Skill::new(
+                style.skill().to_string(),
+                Attribute::Reflexes,
+                level,
+                1,
+            ));
+        }
+        character
+    }
+
+    #[test]
+    fn test_key_attack_bonuses() {
+        use MartialAction::*;
+        let actions = [
+            Strike, Kick, Sweep, Block, Dodge, Disarm, Grapple, Throw, Hold, Escape, Choke,
+        ];
+        let bonuses = |style: MartialArtsStyle| -> Vec<i32> {
+            actions
+                .iter()
+                .map(|action| style.key_attack_bonus(*action))
+                .collect()
+        };
+        assert_eq!(bonuses(MartialArtsStyle::Pruegeln), vec![0; 11]);
+        assert_eq!(
+            bonuses(MartialArtsStyle::Boxen),
+            vec![3, 0, 3, 1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            bonuses(MartialArtsStyle::Ringen),
+            vec![0, 0, 2, 0, 0, 0, 4, 3, 4, 4, 2]
+        );
+    }
+
+    #[test]
+    fn test_strike_adds_dam_and_skill_level() {
+        let mut boxer = fighter("Boxer", Some((MartialArtsStyle::Boxen, 4)));
+        let mut target = fighter("Target", None);
+        // 8 + 4 + 3 already beats 10: no to-hit die; location 3 = chest;
+        // 1d3 = 2, + Boxen 4, + DAM 2 -> 8
+        let mut roller = SequenceRoller::new(vec![3, 2]);
+        let attack = MartialArtsAttack::new(
+            MartialArtsStyle::Boxen,
+            MartialAction::Strike,
+            MeleeDefense::None,
+        );
+        let report = martial_arts_attack(&mut boxer, &mut target, &attack, &mut roller).unwrap();
+        assert!(report.succeeded());
+        let hit = report.hit.unwrap();
+        assert_eq!(hit.zone, HitZone::Chest);
+        assert_eq!(hit.damage, 8);
+        assert_eq!(report.stun_check, None);
+    }
+
+    #[test]
+    fn test_throw_forces_a_stun_check_at_minus_two() {
+        let mut wrestler = fighter("Wrestler", Some((MartialArtsStyle::Ringen, 3)));
+        let mut target = fighter("Target", None);
+        // 8 + 3 + 3 + 5 = 19 vs untrained dodge 8 + 4 = 12; location 2 =
+        // chest; 1d6 = 6, + Ringen 3, + DAM 2 -> 11; stun check die 5
+        let mut roller = SequenceRoller::new(vec![5, 4, 2, 6, 5]);
+        let attack = MartialArtsAttack::new(
+            MartialArtsStyle::Ringen,
+            MartialAction::Throw,
+            MeleeDefense::Dodge,
+        );
+        let report = martial_arts_attack(&mut wrestler, &mut target, &attack, &mut roller).unwrap();
+        assert_eq!(report.hit.unwrap().damage, 11);
+        let stun_check = report.stun_check.unwrap();
+        assert!(stun_check.modifiers.contains(&CheckModifier::new(
+            ModifierSource::Situational("thrown".to_string()),
+            -THROW_STUN_MALUS
+        )));
+    }
+
+    #[test]
+    fn test_held_target_gets_choked_past_armor() {
+        let mut wrestler = fighter("Wrestler", Some((MartialArtsStyle::Ringen, 3)));
+        let mut target = fighter("Target", None);
+        let hold = MartialArtsAttack::new(
+            MartialArtsStyle::Ringen,
+            MartialAction::Hold,
+            MeleeDefense::None,
+        )
+        .with_rounds_held(2);
+        let mut roller = SequenceRoller::new(vec![]);
+        let report = martial_arts_attack(&mut wrestler, &mut target, &hold, &mut roller).unwrap();
+        match &report.roll {
+            MeleeRoll::Unopposed(check) => {
+                let labels: Vec<String> = check.modifiers.iter().map(|m| m.to_string()).collect();
+                assert!(labels.contains(&"+4 Ringen Hold".to_string()));
+                assert!(labels.contains(&"+2 rounds held".to_string()));
+            }
+            roll => panic!("expected an unopposed roll, got {:?}", roll),
+        }
+        assert_eq!(report.hit, None);
+
+        // 1d6 = 4, + Ringen 3, no DAM
+        let choke = MartialArtsAttack::new(
+            MartialArtsStyle::Ringen,
+            MartialAction::Choke,
+            MeleeDefense::None,
+        );
+        let mut roller = SequenceRoller::new(vec![4]);
+        let report = martial_arts_attack(&mut wrestler, &mut target, &choke, &mut roller).unwrap();
+        let hit = report.hit.unwrap();
+        assert_eq!((hit.zone, hit.damage, hit.dam), (HitZone::Chest, 7, 0));
+    }
+
+    #[test]
+    fn test_block_defends_with_the_style_bonus() {
+        let mut attacker = fighter("Attacker", None);
+        attacker.skills.push(Skill::new(
+            "Nahkampf allgemein".to_string(),
+            Attribute::Reflexes,
+            3,
+            1,
+        ));
+        attacker.learn_weapon("Combat Knife");
+        let knife = WeaponCatalog::builtin().weapon("Combat Knife").unwrap();
+        let knife_uuid = knife.item.uuid;
+        attacker.inventory.push(Box::new(knife));
+        let mut boxer = fighter("Boxer", Some((MartialArtsStyle::Boxen, 3)));
+        // 8 + 3 + 4 = 15 vs 8 + 3 + 1 + 4 = 16
+        let mut roller = SequenceRoller::new(vec![4, 4]);
+        let attack = MeleeAttack::new(knife_uuid, MeleeDefense::Block(MartialArtsStyle::Boxen));
+        let report = melee_attack(&mut attacker, &mut boxer, &attack, &mut roller).unwrap();
+        assert!(!report.roll.is_hit());
+    }
+
+    #[test]
+    fn test_defenses_and_unknown_styles_are_rejected() {
+        let mut boxer = fighter("Boxer", Some((MartialArtsStyle::Boxen, 3)));
+        let mut target = fighter("Target", None);
+        let mut roller = SequenceRoller::new(vec![]);
+        let block = MartialArtsAttack::new(
+            MartialArtsStyle::Boxen,
+            MartialAction::Block,
+            MeleeDefense::None,
+        );
+        assert_eq!(
+            martial_arts_attack(&mut boxer, &mut target, &block, &mut roller).unwrap_err(),
+            "Block is a defense, declare it as the defender's MeleeDefense"
+        );
+        let kick = MartialArtsAttack::new(
+            MartialArtsStyle::Ringen,
+            MartialAction::Kick,
+            MeleeDefense::None,
+        );
+        assert_eq!(
+            martial_arts_attack(&mut boxer, &mut target, &kick, &mut roller).unwrap_err(),
+            "Character 'Boxer' has no skill named 'Ringen'"
+        );
+        let hold = MartialArtsAttack::new(
+            MartialArtsStyle::Boxen,
+            MartialAction::Hold,
+            MeleeDefense::None,
+        )
+        .with_called_shot(HitZone::Head);
+        assert_eq!(
+            martial_arts_attack(&mut boxer, &mut target, &hold, &mut roller).unwrap_err(),
+            "A Hold can't be a called shot, only a Strike, Kick or Throw"
+        );
+        let brawl = MartialArtsAttack::new(
+            MartialArtsStyle::Pruegeln,
+            MartialAction::Strike,
+            MeleeDefense::None,
+        );
+        assert_eq!(
+            martial_arts_attack(&mut target, &mut boxer, &brawl, &mut roller).unwrap_err(),
+            "Character 'Target' has no skill named 'Prügeln'"
+        );
+    }
+}
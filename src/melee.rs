@@ -0,0 +1,483 @@
+use crate::armor::HitZone;
+use crate::character::{Attribute, Character, CheckSpec, HitOutcome};
+use crate::dice::{
+    CheckModifier, CheckResult, DiceExpression, DieRoller, Difficulty, ModifierSource, Situation,
+};
+use crate::hit_location::{called_shot_modifier, HitLocationTable};
+use crate::martial_arts::{MartialAction, MartialArtsStyle};
+use crate::opposed::{resolve_opposed, OpposedResult, OpposedWinner};
+use crate::skill_tree::MELEE_SKILLS;
+use crate::weapons::{DamageType, MeleeClass, Weapon};
+use uuid::Uuid;
+
+/// The skill dodges are rolled on: Dodge is part of general melee and stays
+/// at its level, capped at 3 (Q24).
+pub const DODGE_SKILL: &str = MELEE_SKILLS.general;
+
+/// How the defender meets a melee attack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeleeDefense {
+    /// Surprised or busy: the attacker rolls against an easy (10) check.
+    None,
+    /// Dodging on [`DODGE_SKILL`].
+    Dodge,
+    /// Parrying with a carried melee weapon, on that weapon's skill.
+    Parry(Uuid),
+    /// Blocking bare-handed, on the style's skill plus its Block bonus.
+    Block(MartialArtsStyle),
+}
+
+/// A melee attack with the weapon `weapon` against the given defense.
+/// Situation, called shot, hit location table and committed luck are set
+/// with the `with_*` methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeleeAttack {
+    pub weapon: Uuid,
+    pub defense: MeleeDefense,
+    pub situation: Situation,
+    pub called_shot: Option<HitZone>,
+    /// The table random hits are located on, CP2020 by default.
+    pub hit_locations: HitLocationTable,
+    pub luck: i32,
+}
+
+impl MeleeAttack {
+    pub fn new(weapon: Uuid, defense: MeleeDefense) -> Self {
+        MeleeAttack {
+            weapon,
+            defense,
+            situation: Situation::new(),
+            called_shot: None,
+            hit_locations: HitLocationTable::default(),
+            luck: 0,
+        }
+    }
+
+    pub fn with_situation(mut self, situation: Situation) -> Self {
+        self.situation = situation;
+        self
+    }
+
+    /// Aims at `zone` for [`CALLED_SHOT_MALUS`](crate::CALLED_SHOT_MALUS).
+    pub fn with_called_shot(mut self, zone: HitZone) -> Self {
+        self.called_shot = Some(zone);
+        self
+    }
+
+    pub fn with_hit_locations(mut self, table: HitLocationTable) -> Self {
+        self.hit_locations = table;
+        self
+    }
+
+    pub fn with_luck(mut self, luck: i32) -> Self {
+        self.luck = luck;
+        self
+    }
+}
+
+/// The to-hit roll of a melee attack.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MeleeRoll {
+    /// Against an undefended target.
+    Unopposed(CheckResult),
+    /// Attacker (first) against the defender's dodge or parry (second).
+    Opposed(OpposedResult),
+}
+
+impl MeleeRoll {
+    /// Whether the attack connects. The defender wins ties: the status quo
+    /// gets the benefit of the doubt.
+    pub fn is_hit(&self) -> bool {
+        match self {
+            MeleeRoll::Unopposed(check) => check.outcome.is_success(),
+            MeleeRoll::Opposed(result) => result.winner == OpposedWinner::First,
+        }
+    }
+}
+
+/// A blow that connected.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MeleeHit {
+    pub zone: HitZone,
+    /// Rolled damage plus the attacker's DAM (and a martial artist's skill
+    /// level), never below 0.
+    pub damage: i32,
+    /// The attacker's DAM included in `damage`, see [`Character::dam`].
+    pub dam: i32,
+    pub outcome: HitOutcome,
+}
+
+/// Everything a melee attack did.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MeleeReport {
+    pub class: MeleeClass,
+    pub roll: MeleeRoll,
+    /// `None` on a miss, dodge or parry.
+    pub hit: Option<MeleeHit>,
+}
+
+/// Resolves a melee attack from to-hit roll to damage on the defender.
+///
+/// The attacker rolls on the weapon's skill with the weapon accuracy and the
+/// declared situation against the defense: an easy (10) check if the
+/// defender doesn't defend, otherwise an open roll against the dodge, parry
+/// or block. An unfamiliar weapon shifts the roll by
+/// [`UNFAMILIAR_WEAPON_SHIFT`](crate::combat::UNFAMILIAR_WEAPON_SHIFT). A
+/// hit lands on the called zone or a location rolled on the attack's table,
+/// deals the weapon damage plus the attacker's DAM and goes through
+/// [`Character::hit`] — never as a gunshot.
+///
+/// Both sides are validated (weapons, skills, luck) before anything is
+/// spent or rolled.
+pub fn melee_attack(
+    attacker: &mut Character,
+    defender: &mut Character,
+    attack: &MeleeAttack,
+    roller: &mut dyn DieRoller,
+) -> Result<MeleeReport, String> {
+    let weapon = attacker.weapon(attack.weapon)?.clone();
+    let class = melee_class(&weapon)?;
+    let mut situation = attack.situation.clone();
+    if weapon.accuracy != 0 {
+        situation = situation.with_modifier(CheckModifier::new(
+            ModifierSource::WeaponAccuracy(weapon.item.name.clone()),
+            weapon.accuracy,
+        ));
+    }
+    if let Some(zone) = attack.called_shot {
+        situation = situation.with_modifier(called_shot_modifier(zone));
+    }
+    if let Some(shift) = attacker.unfamiliar_weapon_shift(&weapon) {
+        situation = situation.with_difficulty_shift(shift);
+    }
+    let roll = roll_melee(
+        attacker,
+        &CheckSpec::Skill(weapon.skill.clone()),
+        attack.luck,
+        &situation,
+        defender,
+        attack.defense,
+        roller,
+    )?;
+    attacker.practice_weapon(&weapon);
+
+    let hit = if roll.is_hit() {
+        let zone = attack
+            .hit_locations
+            .locate(attack.called_shot, &defender.name, roller);
+        Some(land_blow(
+            attacker,
+            defender,
+            zone,
+            &weapon.damage,
+            weapon.damage_type,
+            &weapon.item.name,
+            roller,
+        ))
+    } else {
+        None
+    };
+    Ok(MeleeReport { class, roll, hit })
+}
+
+/// The to-hit roll every melee and martial arts attack shares: the attacker
+/// rolls on `attack_spec` with the situation against an easy (10) check if
+/// the defender doesn't defend, otherwise openly against the defender's
+/// dodge, parry or block. Defenders without the skill roll on bare REF; an
+/// unfamiliar parrying weapon counts against the parry, a block gets the
+/// style's key-attack bonus.
+///
+/// Both sides are validated before anything is spent or rolled.
+pub(crate) fn roll_melee(
+    attacker: &mut Character,
+    attack_spec: &CheckSpec,
+    luck: i32,
+    situation: &Situation,
+    defender: &mut Character,
+    defense: MeleeDefense,
+    roller: &mut dyn DieRoller,
+) -> Result<MeleeRoll, String> {
+    attacker.check_values(attack_spec)?;
+    attacker.check_luck(luck)?;
+    let mut parrying = None;
+    let mut defense_situation = Situation::new();
+    let defense_spec = match defense {
+        MeleeDefense::None => None,
+        MeleeDefense::Dodge => Some(skill_or_reflexes(defender, DODGE_SKILL)),
+        MeleeDefense::Parry(uuid) => {
+            let weapon = defender.weapon(uuid)?.clone();
+            melee_class(&weapon)?;
+            if let Some(shift) = defender.unfamiliar_weapon_shift(&weapon) {
+                defense_situation = defense_situation.with_difficulty_shift(shift);
+            }
+            let spec = skill_or_reflexes(defender, &weapon.skill);
+            parrying = Some(weapon);
+            Some(spec)
+        }
+        MeleeDefense::Block(style) => {
+            let spec = CheckSpec::Skill(style.skill().to_string());
+            defender.check_values(&spec)?;
+            defense_situation =
+                defense_situation.with_modifier(style.key_attack_modifier(MartialAction::Block));
+            Some(spec)
+        }
+    };
+
+    let roll = match defense_spec {
+        None => MeleeRoll::Unopposed(attacker.roll_check(
+            attack_spec,
+            luck,
+            situation,
+            Difficulty::Easy,
+            roller,
+        )?),
+        Some(spec) => {
+            let attack_roll = attacker.open_check_with(attack_spec, luck, situation, roller)?;
+            let defense_roll = defender.open_check_with(&spec, 0, &defense_situation, roller)?;
+            MeleeRoll::Opposed(resolve_opposed(attack_roll, defense_roll))
+        }
+    };
+    if let Some(parrying) = &parrying {
+        defender.practice_weapon(parrying);
+    }
+    Ok(roll)
+}
+
+/// Lands a blow on `zone` of the defender: `dice` plus the attacker's DAM.
+/// `source` names the blow in the roll log.
+pub(crate) fn land_blow(
+    attacker: &Character,
+    defender: &mut Character,
+    zone: HitZone,
+    dice: &DiceExpression,
+    damage_type: DamageType,
+    source: &str,
+    roller: &mut dyn DieRoller,
+) -> MeleeHit {
+    roller.annotate(&format!("{}: damage", source));
+    let dam = attacker.dam();
+    let damage = (dice.roll(roller).total + dam).max(0);
+    let outcome = defender.hit(damage, zone, damage_type, false, roller);
+    MeleeHit {
+        zone,
+        damage,
+        dam,
+        outcome,
+    }
+}
+
+fn melee_class(weapon: &Weapon) -> Result<MeleeClass, String> {
+    weapon.category.melee_class().ok_or_else(|| {
+        format!(
+            "Weapon '{}' is a ranged weapon, not a melee one",
+            weapon.item.name
+        )
+    })
+}
+
+/// Rolls on `skill` if the character has it, untrained on REF otherwise.
+pub(crate) fn skill_or_reflexes(character: &Character, skill: &str) -> CheckSpec {
+    if character.skill_level(skill).is_some() {
+        CheckSpec::Skill(skill.to_string())
+    } else {
+        CheckSpec::Attribute(Attribute::Reflexes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::tests::{set_attribute, solo};
+    use crate::character::Skill;
+    use crate::dice::SequenceRoller;
+    use crate::weapons::WeaponCatalog;
+
+    fn fighter(name: &str, melee: i32) -> Character {
+        let mut character = solo(name);
+        if melee > 0 {
+            character.skills.push(Skill::new(
+                DODGE_SKILL.to_string(),
+                Attribute::Reflexes,
+                melee,
+                1,
+            ));
+        }
+        character
+    }
+
+    fn arm(character: &mut Character, weapon: &str) -> Uuid {
+        character.learn_weapon(weapon);
+        let weapon = WeaponCatalog::builtin().weapon(weapon).unwrap();
+        let uuid = weapon.item.uuid;
+        character.inventory.push(Box::new(weapon));
+        uuid
+    }
+
+    #[test]
+    fn test_dam_table() {
+        let mut character = fighter("Fighter", 0);
+        let dams: Vec<i32> = [2, 3, 5, 8, 10, 11, 13, 15]
+            .iter()
+            .map(|&body| {
+                set_attribute(&mut character, Attribute::Body, body);
+                character.dam()
+            })
+            .collect();
+        assert_eq!(dams, vec![-2, -1, 0, 1, 2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn test_undefended_target_takes_weapon_damage_plus_dam() {
+        let mut attacker = fighter("Attacker", 3);
+        let knife = arm(&mut attacker, "Combat Knife");
+        let mut defender = fighter("Defender", 0);
+        // 8 + 3 = 11 already beats 10: no to-hit die; location 2 = chest;
+        // 1d6+1 = 4 + 1, DAM +2 -> 7, BTM 4 -> 3 real damage
+        let mut roller = SequenceRoller::new(vec![2, 4]);
+        let report = melee_attack(
+            &mut attacker,
+            &mut defender,
+            &MeleeAttack::new(knife, MeleeDefense::None),
+            &mut roller,
+        )
+        .unwrap();
+        assert_eq!(report.class, MeleeClass::Short);
+        assert!(report.roll.is_hit());
+        let hit = report.hit.unwrap();
+        assert_eq!(hit.zone, HitZone::Chest);
+        assert_eq!(hit.damage, 7);
+        assert_eq!(hit.dam, 2);
+        assert!(!hit.outcome.through_and_through);
+        assert_eq!(defender.current_damage, 3);
+    }
+
+    #[test]
+    fn test_called_shot_needs_a_roll_and_skips_the_location() {
+        let mut attacker = fighter("Attacker", 3);
+        let knife = arm(&mut attacker, "Combat Knife");
+        let mut defender = fighter("Defender", 0);
+        // 8 + 3 - 4 = 7: die 5 -> 12 vs 10; 1d6+1 = 4 + 1, DAM +2 -> 7
+        let mut roller = SequenceRoller::new(vec![5, 4]);
+        let attack =
+            MeleeAttack::new(knife, MeleeDefense::None).with_called_shot(HitZone::LeftFoot);
+        let report = melee_attack(&mut attacker, &mut defender, &attack, &mut roller).unwrap();
+        match &report.roll {
+            MeleeRoll::Unopposed(check) => {
+                assert_eq!(check.total, 12);
+                let labels: Vec<String> = check.modifiers.iter().map(|m| m.to_string()).collect();
+                assert!(labels.contains(&"-4 called shot LeftFoot".to_string()));
+            }
+            roll => panic!("expected an unopposed roll, got {:?}", roll),
+        }
+        let hit = report.hit.unwrap();
+        assert_eq!((hit.zone, hit.damage), (HitZone::LeftFoot, 7));
+    }
+
+    #[test]
+    fn test_dodge_wins_ties() {
+        let mut attacker = fighter("Attacker", 3);
+        let club = arm(&mut attacker, "Club");
+        // untrained: the dodge rolls on bare REF
+        let mut defender = fighter("Defender", 0);
+        // 8 + 3 + 5 = 16 vs 8 + 8 = 16
+        let mut roller = SequenceRoller::new(vec![5, 8]);
+        let report = melee_attack(
+            &mut attacker,
+            &mut defender,
+            &MeleeAttack::new(club, MeleeDefense::Dodge),
+            &mut roller,
+        )
+        .unwrap();
+        assert!(!report.roll.is_hit());
+        assert_eq!(report.hit, None);
+        assert_eq!(defender.current_damage, 0);
+    }
+
+    #[test]
+    fn test_parry_is_rolled_on_the_parrying_weapon_skill() {
+        let mut attacker = fighter("Attacker", 3);
+        let axe = arm(&mut attacker, "Axe");
+        let mut defender = fighter("Defender", 2);
+        let club = arm(&mut defender, "Club");
+        // Axe WA -1: 8 + 3 - 1 + 7 = 17 vs 8 + 2 + 3 = 13; location 9 = left
+        // leg; 2d6+3 = 3 + 3 + 3, DAM +2 -> 11
+        let mut roller = SequenceRoller::new(vec![7, 3, 9, 3, 3]);
+        let report = melee_attack(
+            &mut attacker,
+            &mut defender,
+            &MeleeAttack::new(axe, MeleeDefense::Parry(club)),
+            &mut roller,
+        )
+        .unwrap();
+        match &report.roll {
+            MeleeRoll::Opposed(result) => {
+                assert_eq!(result.first.total, 17);
+                assert_eq!(result.second.total, 13);
+            }
+            roll => panic!("expected an opposed roll, got {:?}", roll),
+        }
+        let hit = report.hit.unwrap();
+        assert_eq!(hit.zone, HitZone::LeftLeg);
+        assert_eq!(hit.damage, 11);
+    }
+
+    #[test]
+    fn test_unfamiliar_parrying_weapon_counts_against_the_parry() {
+        let mut attacker = fighter("Attacker", 3);
+        let knife = arm(&mut attacker, "Combat Knife");
+        let mut defender = fighter("Defender", 3);
+        let axe = arm(&mut defender, "Axe");
+        defender.familiar_weapons.clear();
+        // 8 + 3 + 4 = 15 vs 8 + 3 - 3 + 6 = 14: the knife gets through
+        let mut roller = SequenceRoller::new(vec![4, 6, 1, 1]);
+        let report = melee_attack(
+            &mut attacker,
+            &mut defender,
+            &MeleeAttack::new(knife, MeleeDefense::Parry(axe)),
+            &mut roller,
+        )
+        .unwrap();
+        match &report.roll {
+            MeleeRoll::Opposed(result) => {
+                assert_eq!(result.second.total, 14);
+                assert_eq!(
+                    result.second.difficulty_shifts[0].to_string(),
+                    "+3 unfamiliar Axe"
+                );
+            }
+            roll => panic!("expected an opposed roll, got {:?}", roll),
+        }
+        assert!(report.roll.is_hit());
+        assert_eq!(defender.weapon_practice["Axe"], 1);
+    }
+
+    #[test]
+    fn test_melee_needs_melee_weapons() {
+        let mut attacker = fighter("Attacker", 3);
+        let grach = arm(&mut attacker, "Grach");
+        let knife = arm(&mut attacker, "Combat Knife");
+        let mut defender = fighter("Defender", 2);
+        let sling = arm(&mut defender, "Sling");
+        let mut roller = SequenceRoller::new(vec![]);
+        assert_eq!(
+            melee_attack(
+                &mut attacker,
+                &mut defender,
+                &MeleeAttack::new(grach, MeleeDefense::None),
+                &mut roller
+            )
+            .unwrap_err(),
+            "Weapon 'Grach' is a ranged weapon, not a melee one"
+        );
+        assert_eq!(
+            melee_attack(
+                &mut attacker,
+                &mut defender,
+                &MeleeAttack::new(knife, MeleeDefense::Parry(sling)),
+                &mut roller
+            )
+            .unwrap_err(),
+            "Weapon 'Sling' is a ranged weapon, not a melee one"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use crate::character::{Character, CheckSpec};
+use crate::dice::{DieRoller, OpenRollResult};
+
+/// Who won an opposed roll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpposedWinner {
+    First,
+    Second,
+    /// Equal totals or both fumbled. Who gets the benefit of the doubt
+    /// (usually the defender, i.e. the status quo) is up to the GM.
+    Tie,
+}
+
+/// Outcome of a contest between two open rolls.
+#[derive(Debug, PartialEq, Eq)]
+pub struct OpposedResult {
+    pub winner: OpposedWinner,
+    /// How far the winner's total lies above the loser's; 0 on a tie.
+    /// Can be 0 when a fumble decided the contest against the higher total.
+    pub margin: i32,
+    pub first: OpenRollResult,
+    pub second: OpenRollResult,
+}
+
+/// Decides a contest between two open rolls: the higher total wins, but a
+/// fumble always loses against a roll that didn't fumble.
+pub fn resolve_opposed(first: OpenRollResult, second: OpenRollResult) -> OpposedResult {
+    let winner = match (first.is_fumble, second.is_fumble) {
+        (false, true) => OpposedWinner::First,
+        (true, false) => OpposedWinner::Second,
+        (true, true) => OpposedWinner::Tie,
+        (false, false) => match first.total.cmp(&second.total) {
+            std::cmp::Ordering::Greater => OpposedWinner::First,
+            std::cmp::Ordering::Less => OpposedWinner::Second,
+            std::cmp::Ordering::Equal => OpposedWinner::Tie,
+        },
+    };
+    let margin = match winner {
+        OpposedWinner::First => (first.total - second.total).max(0),
+        OpposedWinner::Second => (second.total - first.total).max(0),
+        OpposedWinner::Tie => 0,
+    };
+    OpposedResult {
+        winner,
+        margin,
+        first,
+        second,
+    }
+}
+
+/// Rolls a contest between two characters (stealth vs perception, grapple
+/// vs escape, …): both roll openly via [`Character::open_check`] with their
+/// effective values, committed luck and pending maluses; the first character
+/// rolls first.
+///
+/// Both sides are validated (skill present, luck in the pool) before
+/// anything is spent or rolled.
+pub fn opposed_check(
+    first: &mut Character,
+    first_spec: &CheckSpec,
+    first_luck: i32,
+    second: &mut Character,
+    second_spec: &CheckSpec,
+    second_luck: i32,
+    roller: &mut dyn DieRoller,
+) -> Result<OpposedResult, String> {
+    first.check_values(first_spec)?;
+    first.check_luck(first_luck)?;
+    second.check_values(second_spec)?;
+    second.check_luck(second_luck)?;
+    let first_roll = first.open_check(first_spec, first_luck, roller)?;
+    let second_roll = second.open_check(second_spec, second_luck, roller)?;
+    Ok(resolve_opposed(first_roll, second_roll))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::tests::{set_attribute, solo};
+    use crate::character::{Attribute, Skill};
+    use crate::dice::SequenceRoller;
+
+    fn character(name: &str, refl: i32, int: i32) -> Character {
+        let mut character = solo(name);
+        set_attribute(&mut character, Attribute::Reflexes, refl);
+        set_attribute(&mut character, Attribute::Intelligence, int);
+        character
+    }
+
+    #[test]
+    fn test_stealth_vs_perception() {
+        let mut sneak = character("Sneak", 8, 5);
+        sneak.skills.push(Skill::new(
+            "Schleichen".to_string(),
+            Attribute::Reflexes,
+            5,
+            1,
+        ));
+        let mut guard = character("Guard", 6, 6);
+        guard.skills.push(Skill::new(
+            "Wahrnehmung".to_string(),
+            Attribute::Intelligence,
+            3,
+            1,
+        ));
+
+        // Sneak: 8 + 5 + 4 = 17; Guard: 6 + 3 + 1 luck + 6 = 16
+        let mut roller = SequenceRoller::new(vec![4, 6]);
+        let result = opposed_check(
+            &mut sneak,
+            &CheckSpec::Skill("Schleichen".to_string()),
+            0,
+            &mut guard,
+            &CheckSpec::Skill("Wahrnehmung".to_string()),
+            1,
+            &mut roller,
+        )
+        .unwrap();
+        assert_eq!(result.winner, OpposedWinner::First);
+        assert_eq!(result.margin, 1);
+        assert_eq!(result.first.total, 17);
+        assert_eq!(result.second.total, 16);
+        assert_eq!(guard.current_luck, 4);
+    }
+
+    #[test]
+    fn test_pending_malus_and_attribute_contest() {
+        let mut grappler = character("Grappler", 6, 5);
+        let mut victim = character("Victim", 6, 5);
+        victim.pending_roll_malus = 2;
+
+        // BODY 7 + 5 = 12 vs BODY 7 - 2 + 7 = 12: tie, malus consumed
+        let mut roller = SequenceRoller::new(vec![5, 7]);
+        let result = opposed_check(
+            &mut grappler,
+            &CheckSpec::Attribute(Attribute::Body),
+            0,
+            &mut victim,
+            &CheckSpec::Attribute(Attribute::Body),
+            0,
+            &mut roller,
+        )
+        .unwrap();
+        assert_eq!(result.winner, OpposedWinner::Tie);
+        assert_eq!(result.margin, 0);
+        assert_eq!(victim.pending_roll_malus, 0);
+    }
+
+    #[test]
+    fn test_fumble_loses_against_lower_total() {
+        let fumbled = OpenRollResult {
+            total: 14,
+            die_rolls: vec![1],
+            is_fumble: true,
+            fumble_confirmation: Some(4),
+            difficulty_shifts: Vec::new(),
+        };
+        let plain = OpenRollResult {
+            total: 9,
+            die_rolls: vec![2],
+            is_fumble: false,
+            fumble_confirmation: None,
+            difficulty_shifts: Vec::new(),
+        };
+        let result = resolve_opposed(fumbled, plain);
+        assert_eq!(result.winner, OpposedWinner::Second);
+        assert_eq!(result.margin, 0);
+        assert_eq!(result.first.fumble_confirmation, Some(4));
+    }
+
+    #[test]
+    fn test_opposed_check_validates_both_sides_first() {
+        let mut first = character("First", 6, 5);
+        let mut second = character("Second", 6, 5);
+        let mut roller = SequenceRoller::new(vec![]);
+        let error = opposed_check(
+            &mut first,
+            &CheckSpec::Attribute(Attribute::Reflexes),
+            2,
+            &mut second,
+            &CheckSpec::Skill("Ringen".to_string()),
+            0,
+            &mut roller,
+        )
+        .unwrap_err();
+        assert_eq!(error, "Character 'Second' has no skill named 'Ringen'");
+        assert_eq!(first.current_luck, 5, "nothing is spent on an error");
+    }
+}
@@ -0,0 +1,210 @@
+use crate::character::{Character, HitOutcome};
+use crate::dice::{skill_check, DieRoller, Difficulty, Outcome, SeededRoller};
+use crate::health::WoundState;
+
+/// Distribution of many simulated skill checks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckStats {
+    pub iterations: u32,
+    pub auto_successes: u32,
+    /// Rolled successes, auto-successes not included.
+    pub successes: u32,
+    pub failures: u32,
+    pub embarrassing_failures: u32,
+    pub critical_failures: u32,
+    pub mean_total: f64,
+}
+
+impl CheckStats {
+    /// Share of checks that succeeded, auto-successes included; 0 without
+    /// any checks.
+    pub fn success_rate(&self) -> f64 {
+        mean(
+            i64::from(self.auto_successes + self.successes),
+            self.iterations,
+        )
+    }
+
+    /// Share of checks that ended in a confirmed fumble (embarrassing or
+    /// critical failure); 0 without any checks.
+    pub fn fumble_rate(&self) -> f64 {
+        mean(
+            i64::from(self.embarrassing_failures + self.critical_failures),
+            self.iterations,
+        )
+    }
+}
+
+/// Rolls `iterations` skill checks with a [`SeededRoller`] started from
+/// `seed`, so two runs with the same seed give the same numbers.
+pub fn simulate_checks(
+    iterations: u32,
+    seed: u64,
+    attribute: i32,
+    skill: i32,
+    luck: i32,
+    difficulty: Difficulty,
+) -> CheckStats {
+    let mut roller = SeededRoller::new(seed);
+    let mut stats = CheckStats {
+        iterations,
+        auto_successes: 0,
+        successes: 0,
+        failures: 0,
+        embarrassing_failures: 0,
+        critical_failures: 0,
+        mean_total: 0.0,
+    };
+    let mut total_sum = 0i64;
+    for _ in 0..iterations {
+        let result = skill_check(attribute, skill, luck, difficulty, &mut roller);
+        total_sum += i64::from(result.total);
+        match result.outcome {
+            Outcome::AutoSuccess => stats.auto_successes += 1,
+            Outcome::Success => stats.successes += 1,
+            Outcome::Failure => stats.failures += 1,
+            Outcome::EmbarrassingFailure => stats.embarrassing_failures += 1,
+            Outcome::CriticalFailure => stats.critical_failures += 1,
+        }
+    }
+    stats.mean_total = mean(total_sum, iterations);
+    stats
+}
+
+/// Distribution of many simulated attacks on the same target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HitStats {
+    pub iterations: u32,
+    /// Mean real damage per attack (converted Prellschaden included).
+    pub mean_real_damage: f64,
+    /// Mean Prellschaden put on the bruise scale per attack.
+    pub mean_bruise: f64,
+    /// Share of attacks after which the target is dead.
+    pub death_rate: f64,
+    /// Share of attacks that ended in a failed KO check.
+    pub knockout_rate: f64,
+    /// Share of attacks where the penetration cap made the shot exit.
+    pub through_and_through_rate: f64,
+}
+
+/// Runs `iterations` attacks, each on a fresh target from `target`, with a
+/// [`SeededRoller`] started from `seed`.
+///
+/// `attack` is the sequence under test: a single [`Character::hit`], or a
+/// full attack with to-hit roll and damage dice. It returns the outcomes of
+/// every hit it landed (none for a miss). A KO check is rolled after the
+/// attack if any hit required one and the target is still alive.
+pub fn simulate_attacks<T, A>(iterations: u32, seed: u64, target: T, mut attack: A) -> HitStats
+where
+    T: Fn() -> Character,
+    A: FnMut(&mut Character, &mut dyn DieRoller) -> Vec<HitOutcome>,
+{
+    let mut roller = SeededRoller::new(seed);
+    let mut real_damage = 0i64;
+    let mut bruise = 0i64;
+    let mut deaths = 0i64;
+    let mut knockouts = 0i64;
+    let mut through_and_through = 0i64;
+    for _ in 0..iterations {
+        let mut character = target();
+        let outcomes = attack(&mut character, &mut roller);
+        real_damage += outcomes
+            .iter()
+            .map(|o| i64::from(o.real_damage))
+            .sum::<i64>();
+        bruise += outcomes
+            .iter()
+            .map(|o| i64::from(o.bruise_added))
+            .sum::<i64>();
+        if outcomes.iter().any(|o| o.through_and_through) {
+            through_and_through += 1;
+        }
+        if character.wound_state() == WoundState::Dead {
+            deaths += 1;
+        } else if outcomes.iter().any(|o| o.ko_check_required)
+            && !character.ko_check(&mut roller).outcome.is_success()
+        {
+            knockouts += 1;
+        }
+    }
+    HitStats {
+        iterations,
+        mean_real_damage: mean(real_damage, iterations),
+        mean_bruise: mean(bruise, iterations),
+        death_rate: mean(deaths, iterations),
+        knockout_rate: mean(knockouts, iterations),
+        through_and_through_rate: mean(through_and_through, iterations),
+    }
+}
+
+fn mean(sum: i64, iterations: u32) -> f64 {
+    if iterations == 0 {
+        0.0
+    } else {
+        sum as f64 / f64::from(iterations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::armor::HitZone;
+    use crate::character::tests::solo;
+    use crate::dice::{skill_check_probabilities, DiceExpression};
+    use crate::DamageType;
+
+    fn target() -> Character {
+        // no armor
+        solo("Target")
+    }
+
+    #[test]
+    fn test_simulated_checks_match_exact_odds() {
+        let stats = simulate_checks(20_000, 7, 6, 3, 0, Difficulty::Normal);
+        let exact = skill_check_probabilities(6, 3, 0, Difficulty::Normal);
+        assert!((stats.success_rate() - exact.success_chance()).abs() < 0.02);
+        // a 1 comes up 10% of the time, half of those confirm as fumbles
+        assert!((stats.fumble_rate() - 0.05).abs() < 0.01);
+        // same seed, same numbers
+        assert_eq!(
+            stats,
+            simulate_checks(20_000, 7, 6, 3, 0, Difficulty::Normal)
+        );
+    }
+
+    #[test]
+    fn test_no_iterations_give_zero_rates() {
+        let stats = simulate_checks(0, 7, 6, 3, 0, Difficulty::Normal);
+        assert_eq!(stats.success_rate(), 0.0);
+        assert_eq!(stats.fumble_rate(), 0.0);
+        assert_eq!(stats.mean_total, 0.0);
+    }
+
+    #[test]
+    fn test_hollow_points_compare_against_regular_rounds() {
+        let damage: DiceExpression = "3d6".parse().unwrap();
+        let shoot = |damage_type: DamageType| {
+            simulate_attacks(5_000, 3, target, |character, roller| {
+                let amount = damage.roll(roller).total;
+                vec![character.hit(amount, HitZone::Stomach, damage_type, true, roller)]
+            })
+        };
+        let regular = shoot(DamageType::Blunt);
+        let hollow_point = shoot(DamageType::HollowPoint);
+
+        // the penetration cap only applies to regular rounds ...
+        assert!(regular.through_and_through_rate > 0.0);
+        assert_eq!(hollow_point.through_and_through_rate, 0.0);
+        // ... and the doubling makes hollow points hit much harder
+        assert!(hollow_point.mean_real_damage > 2.0 * regular.mean_real_damage);
+        assert!(hollow_point.knockout_rate > regular.knockout_rate);
+    }
+
+    #[test]
+    fn test_misses_cause_nothing() {
+        let stats = simulate_attacks(100, 1, target, |_, _| Vec::new());
+        assert_eq!(stats.mean_real_damage, 0.0);
+        assert_eq!(stats.death_rate, 0.0);
+        assert_eq!(stats.knockout_rate, 0.0);
+    }
+}
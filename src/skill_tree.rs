@@ -0,0 +1,41 @@
+/// A general skill that splits into specializations above `split_level`
+/// (Q23): the general skill stops there, and each specialization continues
+/// the scale on its own from the shared base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkillTree {
+    pub general: &'static str,
+    pub split_level: i32,
+    pub specializations: &'static [&'static str],
+}
+
+/// Melee: "Nahkampf allgemein" (which includes Dodge) up to 3, then short,
+/// medium and long weapons.
+pub const MELEE_SKILLS: SkillTree = SkillTree {
+    general: "Nahkampf allgemein",
+    split_level: 3,
+    specializations: &["Kurz", "Mittel", "Lang"],
+};
+
+/// Every skill tree of the rules.
+pub const SKILL_TREES: &[SkillTree] = &[MELEE_SKILLS];
+
+impl SkillTree {
+    /// The tree `skill_name` is the general skill or a specialization of.
+    pub fn of(skill_name: &str) -> Option<&'static SkillTree> {
+        SKILL_TREES
+            .iter()
+            .find(|tree| tree.general == skill_name || tree.specializations.contains(&skill_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_of_general_skill_and_specializations() {
+        assert_eq!(SkillTree::of("Nahkampf allgemein"), Some(&MELEE_SKILLS));
+        assert_eq!(SkillTree::of("Mittel"), Some(&MELEE_SKILLS));
+        assert_eq!(SkillTree::of("Pistole"), None);
+    }
+}
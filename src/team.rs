@@ -0,0 +1,472 @@
+use crate::character::{Character, CheckSpec};
+use crate::dice::{CheckModifier, CheckResult, DieRoller, Difficulty, ModifierSource, Situation};
+
+/// The skill a teacher rolls (against 15) in a teacher–student session.
+pub const SKILL_TEACHING: &str = "Lehren";
+
+/// Outcome of a teacher–student session (Regeln → Teamaktionen → Lehrer-Schüler).
+#[derive(Debug, PartialEq, Eq)]
+pub struct TeachingResult {
+    pub student: CheckResult,
+    pub teacher: CheckResult,
+    /// Points of the teacher's surplus spent on the student's shortfall.
+    pub offset: i32,
+    /// The student succeeded, on their own or through the offset.
+    pub student_succeeded: bool,
+    /// Both succeeded: the student may learn the next level at half price.
+    pub half_price: bool,
+    /// CP price of the student's next level in the skill
+    /// (target level × 10, halved on `half_price`).
+    pub next_level_cost: i32,
+}
+
+/// A teacher–student session: the student rolls on the taught skill, the
+/// teacher rolls [`SKILL_TEACHING`] against 15. The teacher's points above
+/// the target can make up the student's shortfall; if both succeed
+/// afterwards, the student's next level costs half.
+///
+/// The teacher must be better in the skill than the student. A student who
+/// doesn't know the skill yet rolls the bare base attribute. Fumbles can't be
+/// offset — a natural 1 always fails.
+pub fn teach(
+    teacher: &mut Character,
+    student: &mut Character,
+    skill_name: &str,
+    difficulty: Difficulty,
+    roller: &mut dyn DieRoller,
+) -> Result<TeachingResult, String> {
+    let (teacher_skill, teacher_level) = teacher.skill_level(skill_name).ok_or_else(|| {
+        format!(
+            "Character '{}' has no skill named '{}'",
+            teacher.name, skill_name
+        )
+    })?;
+    let base = teacher_skill.base;
+    let student_level = student
+        .skill_level(skill_name)
+        .map_or(0, |(_, level)| level);
+    if teacher_level <= student_level {
+        return Err(format!(
+            "Teacher '{}' ({} {}) must be better than student '{}' ({} {})",
+            teacher.name, skill_name, teacher_level, student.name, skill_name, student_level
+        ));
+    }
+    let student_spec = if student_level > 0 {
+        CheckSpec::Skill(skill_name.to_string())
+    } else {
+        CheckSpec::Attribute(base)
+    };
+    let teacher_spec = CheckSpec::Skill(SKILL_TEACHING.to_string());
+    student.check_values(&student_spec)?;
+    teacher.check_values(&teacher_spec)?;
+
+    let student_check =
+        student.roll_check(&student_spec, 0, &Situation::new(), difficulty, roller)?;
+    let teacher_check = teacher.roll_check(
+        &teacher_spec,
+        0,
+        &Situation::new(),
+        Difficulty::Normal,
+        roller,
+    )?;
+
+    let mut offset = 0;
+    let mut student_succeeded = student_check.outcome.is_success();
+    if !student_succeeded && teacher_check.outcome.is_success() {
+        if let Some(shortfall) = shortfall(&student_check) {
+            if surplus(&teacher_check) >= shortfall {
+                offset = shortfall;
+                student_succeeded = true;
+            }
+        }
+    }
+    let half_price = student_succeeded && teacher_check.outcome.is_success();
+    let full_cost = (student_level + 1) * 10;
+    Ok(TeachingResult {
+        student: student_check,
+        teacher: teacher_check,
+        offset,
+        student_succeeded,
+        half_price,
+        next_level_cost: if half_price { full_cost / 2 } else { full_cost },
+    })
+}
+
+/// Someone taking part in a team action, with the luck they commit.
+pub struct Participant<'a> {
+    pub character: &'a mut Character,
+    pub luck: i32,
+}
+
+impl<'a> Participant<'a> {
+    pub fn new(character: &'a mut Character) -> Self {
+        Participant { character, luck: 0 }
+    }
+
+    pub fn with_luck(mut self, luck: i32) -> Self {
+        self.luck = luck;
+        self
+    }
+}
+
+/// How a skill pool shares the work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolSplit {
+    /// Everyone rolls against the difficulty divided by the number of
+    /// participants (rounded up) and takes the full time.
+    Difficulty,
+    /// Everyone rolls against the full difficulty; the time is divided.
+    Time,
+}
+
+/// One participant's part in a skill pool.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PoolMember {
+    pub name: String,
+    pub check: CheckResult,
+    /// Points above the target handed to others.
+    pub surplus_given: i32,
+    /// Shortfall made up by the others' surplus.
+    pub shortfall_covered: i32,
+    pub succeeded: bool,
+}
+
+/// Outcome of a skill pool (Regeln → Teamaktionen → Gemeinschaftswerk).
+#[derive(Debug, PartialEq, Eq)]
+pub struct PoolResult {
+    /// The difficulty every participant rolled against.
+    pub target: i32,
+    /// The time needed is divided by this (1 for [`PoolSplit::Difficulty`]).
+    pub time_divisor: i32,
+    pub members: Vec<PoolMember>,
+    /// Every participant succeeded after redistribution.
+    pub succeeded: bool,
+}
+
+/// A shared piece of work (writing programs, repairing a truck, cooking):
+/// everyone rolls `skill_name` against the split difficulty or the full one
+/// in split time. Surplus points of successful participants make up the
+/// shortfalls of the others, smallest shortfall first; fumbles can't be
+/// made up. The work succeeds when everybody succeeds in the end.
+///
+/// Example from the wiki: truck repair in one day alone is 35; two people
+/// in one day both roll against 18, two people in half a day both against 35.
+pub fn skill_pool(
+    participants: &mut [Participant],
+    skill_name: &str,
+    difficulty: Difficulty,
+    split: PoolSplit,
+    roller: &mut dyn DieRoller,
+) -> Result<PoolResult, String> {
+    if participants.is_empty() {
+        return Err("A skill pool needs at least one participant".to_string());
+    }
+    let count = participants.len() as i32;
+    let (target, time_divisor) = match split {
+        PoolSplit::Difficulty => ((difficulty.target() + count - 1) / count, 1),
+        PoolSplit::Time => (difficulty.target(), count),
+    };
+    let spec = CheckSpec::Skill(skill_name.to_string());
+    for participant in participants.iter() {
+        participant.character.check_values(&spec)?;
+        participant.character.check_luck(participant.luck)?;
+    }
+
+    let mut members = Vec::new();
+    for participant in participants.iter_mut() {
+        let check = participant.character.roll_check(
+            &spec,
+            participant.luck,
+            &Situation::new(),
+            Difficulty::Custom(target),
+            roller,
+        )?;
+        members.push(PoolMember {
+            name: participant.character.name.clone(),
+            succeeded: check.outcome.is_success(),
+            check,
+            surplus_given: 0,
+            shortfall_covered: 0,
+        });
+    }
+
+    let mut needy: Vec<(usize, i32)> = members
+        .iter()
+        .enumerate()
+        .filter_map(|(index, member)| shortfall(&member.check).map(|needed| (index, needed)))
+        .collect();
+    needy.sort_by_key(|&(_, needed)| needed);
+    for (index, needed) in needy {
+        let available: i32 = members
+            .iter()
+            .filter(|member| member.check.outcome.is_success())
+            .map(|member| surplus(&member.check) - member.surplus_given)
+            .sum();
+        if available < needed {
+            break;
+        }
+        let mut remaining = needed;
+        for giver in members
+            .iter_mut()
+            .filter(|member| member.check.outcome.is_success())
+        {
+            let given = (surplus(&giver.check) - giver.surplus_given).min(remaining);
+            giver.surplus_given += given;
+            remaining -= given;
+        }
+        members[index].shortfall_covered = needed;
+        members[index].succeeded = true;
+    }
+
+    let succeeded = members.iter().all(|member| member.succeeded);
+    Ok(PoolResult {
+        target,
+        time_divisor,
+        members,
+        succeeded,
+    })
+}
+
+/// A check with supporters looking over the roller's shoulder.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SupportedResult {
+    pub check: CheckResult,
+    /// Names of the supporters that counted.
+    pub supporters: Vec<String>,
+    /// The bonus they gave (+1 each).
+    pub bonus: i32,
+}
+
+/// The skill value a supporter needs (sheet attribute + skill level).
+pub const SUPPORTER_MINIMUM: i32 = 10;
+
+/// A check with supporters (Regeln → Teamaktionen → Unterstützer): every
+/// supporter who brings at least 10 in the skill — sheet attribute plus
+/// skill level, as on the character sheet — gives +1 on the roll. The others
+/// are simply not counted.
+pub fn supported_check(
+    character: &mut Character,
+    skill_name: &str,
+    luck: i32,
+    difficulty: Difficulty,
+    supporters: &[&Character],
+    roller: &mut dyn DieRoller,
+) -> Result<SupportedResult, String> {
+    let supporters: Vec<String> = supporters
+        .iter()
+        .filter(|supporter| {
+            supporter
+                .skill_level(skill_name)
+                .is_some_and(|(skill, level)| {
+                    supporter.attributes[&skill.base].actual + level >= SUPPORTER_MINIMUM
+                })
+        })
+        .map(|supporter| supporter.name.clone())
+        .collect();
+    let bonus = supporters.len() as i32;
+    let situation = supporters.iter().fold(Situation::new(), |situation, name| {
+        situation.with_modifier(CheckModifier::new(
+            ModifierSource::Supporter(name.clone()),
+            1,
+        ))
+    });
+    let check = character.roll_check(
+        &CheckSpec::Skill(skill_name.to_string()),
+        luck,
+        &situation,
+        difficulty,
+        roller,
+    )?;
+    Ok(SupportedResult {
+        check,
+        supporters,
+        bonus,
+    })
+}
+
+/// Points above the target of a successful check.
+fn surplus(check: &CheckResult) -> i32 {
+    (check.total - check.target).max(0)
+}
+
+/// Points missing to the target of a failed check; `None` for successes and
+/// fumbles (which can't be made up).
+fn shortfall(check: &CheckResult) -> Option<i32> {
+    if check.outcome.is_success() || check.fumble_confirmation.is_some() {
+        None
+    } else {
+        Some(check.target - check.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::tests::{set_attribute, solo};
+    use crate::character::{Attribute, Skill};
+    use crate::dice::{Outcome, SequenceRoller};
+
+    fn mechanic(name: &str, level: i32) -> Character {
+        // TECH 6
+        let mut character = solo(name);
+        character.role = "Techie".to_string();
+        set_attribute(&mut character, Attribute::Tech, 6);
+        character.skills.push(Skill::new(
+            "Reparieren".to_string(),
+            Attribute::Tech,
+            level,
+            1,
+        ));
+        character
+    }
+
+    #[test]
+    fn test_teacher_offsets_student_failure() {
+        let mut teacher = mechanic("Teacher", 6);
+        teacher.skills.push(Skill::new(
+            SKILL_TEACHING.to_string(),
+            Attribute::Empathy,
+            4,
+            1,
+        ));
+        let mut student = mechanic("Student", 2);
+
+        // student: TECH 6 + 2 + 5 = 13 vs 15 (short 2);
+        // teacher: EMP 5 + 4 + 8 = 17 vs 15 (2 over)
+        let mut roller = SequenceRoller::new(vec![5, 8]);
+        let result = teach(
+            &mut teacher,
+            &mut student,
+            "Reparieren",
+            Difficulty::Normal,
+            &mut roller,
+        )
+        .unwrap();
+        assert_eq!(result.student.outcome, Outcome::Failure);
+        assert_eq!(result.offset, 2);
+        assert!(result.student_succeeded);
+        assert!(result.half_price);
+        // level 3 costs 30 CP, halved
+        assert_eq!(result.next_level_cost, 15);
+    }
+
+    #[test]
+    fn test_teaching_surplus_too_small() {
+        let mut teacher = mechanic("Teacher", 6);
+        teacher.skills.push(Skill::new(
+            SKILL_TEACHING.to_string(),
+            Attribute::Empathy,
+            4,
+            1,
+        ));
+        let mut student = mechanic("Student", 2);
+        // student short 3, teacher only 1 over
+        let mut roller = SequenceRoller::new(vec![4, 7]);
+        let result = teach(
+            &mut teacher,
+            &mut student,
+            "Reparieren",
+            Difficulty::Normal,
+            &mut roller,
+        )
+        .unwrap();
+        assert_eq!(result.offset, 0);
+        assert!(!result.student_succeeded);
+        assert!(!result.half_price);
+        assert_eq!(result.next_level_cost, 30);
+    }
+
+    #[test]
+    fn test_teacher_must_be_better() {
+        let mut teacher = mechanic("Teacher", 3);
+        let mut student = mechanic("Student", 3);
+        let mut roller = SequenceRoller::new(vec![]);
+        let error = teach(
+            &mut teacher,
+            &mut student,
+            "Reparieren",
+            Difficulty::Normal,
+            &mut roller,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "Teacher 'Teacher' (Reparieren 3) must be better than student 'Student' (Reparieren 3)"
+        );
+    }
+
+    #[test]
+    fn test_skill_pool_splits_difficulty_and_redistributes() {
+        let mut first = mechanic("First", 5);
+        let mut second = mechanic("Second", 1);
+        let mut participants = vec![Participant::new(&mut first), Participant::new(&mut second)];
+        // wiki truck: 35 split by two -> 18 each.
+        // First: 6 + 5 + 10 + 2 = 23 (5 over); Second: 6 + 1 + 7 = 14 (4 short)
+        let mut roller = SequenceRoller::new(vec![10, 2, 7]);
+        let result = skill_pool(
+            &mut participants,
+            "Reparieren",
+            Difficulty::Custom(35),
+            PoolSplit::Difficulty,
+            &mut roller,
+        )
+        .unwrap();
+        assert_eq!(result.target, 18);
+        assert_eq!(result.time_divisor, 1);
+        assert!(result.succeeded);
+        assert_eq!(result.members[0].surplus_given, 4);
+        assert_eq!(result.members[1].shortfall_covered, 4);
+        assert!(result.members[1].succeeded);
+    }
+
+    #[test]
+    fn test_skill_pool_split_time_and_fumbles() {
+        let mut first = mechanic("First", 5);
+        let mut second = mechanic("Second", 5);
+        let mut participants = vec![
+            Participant::new(&mut first).with_luck(1),
+            Participant::new(&mut second),
+        ];
+        // both vs full 20, half the time. First: 6 + 5 + (9 + 1 luck, explodes) + 3 = 24;
+        // Second fumbles: can't be made up
+        let mut roller = SequenceRoller::new(vec![9, 3, 1, 7]);
+        let result = skill_pool(
+            &mut participants,
+            "Reparieren",
+            Difficulty::Hard,
+            PoolSplit::Time,
+            &mut roller,
+        )
+        .unwrap();
+        assert_eq!(result.target, 20);
+        assert_eq!(result.time_divisor, 2);
+        assert!(!result.succeeded);
+        assert_eq!(result.members[0].surplus_given, 0);
+        assert!(!result.members[1].succeeded);
+        assert_eq!(first.current_luck, 4);
+    }
+
+    #[test]
+    fn test_supporters_with_ten_give_bonus() {
+        let mut worker = mechanic("Worker", 3);
+        // TECH 6 + 4 = 10: counts
+        let expert = mechanic("Expert", 4);
+        // 9: doesn't
+        let helper = mechanic("Helper", 3);
+        // TECH 6 + 3 + 1 supporter + 5 = 15
+        let mut roller = SequenceRoller::new(vec![5]);
+        let result = supported_check(
+            &mut worker,
+            "Reparieren",
+            0,
+            Difficulty::Normal,
+            &[&expert, &helper],
+            &mut roller,
+        )
+        .unwrap();
+        assert_eq!(result.supporters, vec!["Expert".to_string()]);
+        assert_eq!(result.bonus, 1);
+        assert_eq!(result.check.total, 15);
+        assert!(result.check.outcome.is_success());
+    }
+}
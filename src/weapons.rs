@@ -1,3 +1,6 @@
+use crate::dice::DiceExpression;
+use crate::inventory::{InventoryItem, Item};
+use crate::skill_tree::MELEE_SKILLS;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -15,10 +18,603 @@ impl fmt::Display for DamageType {
     }
 }
 
+/// Weapon categories from issue #21.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum WeaponCategory {
+    Knife,
+    Club,
+    Axe,
+    Lance,
+    BrassKnuckles,
+    Pistol,
+    Smg,
+    Rifle,
+    Shotgun,
+    Bow,
+    RocketLauncher,
+    Sling,
+    Shuriken,
+    Molotov,
+}
+
+impl WeaponCategory {
+    /// Hand-to-hand weapons, used with the melee skills.
+    pub fn is_melee(self) -> bool {
+        self.melee_class().is_some()
+    }
+
+    /// The length class of hand-to-hand weapons, `None` for all others.
+    pub fn melee_class(self) -> Option<MeleeClass> {
+        match self {
+            WeaponCategory::Knife | WeaponCategory::BrassKnuckles => Some(MeleeClass::Short),
+            WeaponCategory::Club | WeaponCategory::Axe => Some(MeleeClass::Medium),
+            WeaponCategory::Lance => Some(MeleeClass::Long),
+            _ => None,
+        }
+    }
+
+    /// Guns firing bullets or shot: their hits are gunshots, subject to the
+    /// penetration cap (see [`Character::hit`](crate::Character::hit)).
+    pub fn is_firearm(self) -> bool {
+        matches!(
+            self,
+            WeaponCategory::Pistol
+                | WeaponCategory::Smg
+                | WeaponCategory::Rifle
+                | WeaponCategory::Shotgun
+        )
+    }
+}
+
+impl fmt::Display for WeaponCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Length class of a melee weapon: short (knives, knuckles), medium (clubs,
+/// axes) or long (lances, polearms).
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum MeleeClass {
+    Short,
+    Medium,
+    Long,
+}
+
+impl MeleeClass {
+    /// The melee specialization weapons of this class are used with.
+    pub fn skill(self) -> &'static str {
+        let specializations = MELEE_SKILLS.specializations;
+        match self {
+            MeleeClass::Short => specializations[0],
+            MeleeClass::Medium => specializations[1],
+            MeleeClass::Long => specializations[2],
+        }
+    }
+}
+
+impl fmt::Display for MeleeClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Weapon reliability (CP2020 "Rel"): how likely a fumble jams the weapon.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum Reliability {
+    VeryReliable,
+    Standard,
+    Unreliable,
+}
+
+impl Reliability {
+    /// Highest d10 result that makes a fumbled shot a malfunction.
+    pub fn malfunction_threshold(self) -> i32 {
+        match self {
+            Reliability::VeryReliable => 3,
+            Reliability::Standard => 5,
+            Reliability::Unreliable => 8,
+        }
+    }
+}
+
+/// Why a firearm won't fire until it is cleared.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum Malfunction {
+    /// A dud round: clearing it takes an action.
+    Misfire,
+    /// Jammed after a critical fumble: clearing it takes a Tech check.
+    Jammed,
+}
+
+impl fmt::Display for Malfunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Where the weapon can be hidden (CP2020 "Conc"): pocket, jacket, long
+/// coat, or not at all.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum Concealability {
+    Pocket,
+    Jacket,
+    LongCoat,
+    NotHideable,
+}
+
+/// How hard the weapon is to get (CP2020 "Avail"). Kept for classic CP2020
+/// settings, see Q28.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum Availability {
+    Excellent,
+    Common,
+    Poor,
+    Rare,
+}
+
+/// A weapon: an inventory item plus the stats of the CP2020 weapon tables.
+///
+/// Built with [`Weapon::new`] from the stats every weapon has; the rest
+/// defaults to a single-shot, melee-range, standard weapon and is set with
+/// the `with_*` methods.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Weapon {
+    pub category: WeaponCategory,
+    /// The skill attacks with this weapon are rolled on.
+    pub skill: String,
+    pub damage: DiceExpression,
+    /// Damage type of the weapon itself; for weapons with a magazine the
+    /// loaded rounds decide.
+    pub damage_type: DamageType,
+    /// Ammunition the weapon takes ("9mm", "7.62x39", …); `None` for
+    /// weapons without ammunition.
+    #[serde(default)]
+    pub caliber: Option<String>,
+    /// Weapon accuracy (WA), added to attack rolls.
+    pub accuracy: i32,
+    /// Range in meters; 1 for melee weapons.
+    pub range_m: i32,
+    /// Shots (or blows) per round.
+    pub rate_of_fire: i32,
+    /// Shots per magazine; 0 for weapons without one.
+    pub magazine_size: i32,
+    pub reliability: Reliability,
+    pub concealability: Concealability,
+    pub availability: Availability,
+    /// Set by a fumbled shot, see [`Reliability`]; the weapon doesn't fire
+    /// until it is cleared.
+    #[serde(default)]
+    pub malfunction: Option<Malfunction>,
+    /// Loaded rounds, bottom to top: the last entry fires first.
+    #[serde(default)]
+    pub magazine: Vec<LoadedRounds>,
+    pub item: Item,
+}
+
+/// A run of identical rounds in a magazine.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct LoadedRounds {
+    pub caliber: String,
+    pub damage_type: DamageType,
+    pub count: i32,
+}
+
+impl InventoryItem for Weapon {
+    fn get_item(&self) -> &Item {
+        &self.item
+    }
+
+    fn get_item_mut(&mut self) -> &mut Item {
+        &mut self.item
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+    fn equals(&self, other: &dyn InventoryItem) -> bool {
+        if let Some(other_weapon) = other.as_any().downcast_ref::<Weapon>() {
+            self == other_weapon
+        } else {
+            false
+        }
+    }
+}
+
+impl fmt::Display for Weapon {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} WA {:+}, {}m, ROF {}",
+            self.item,
+            self.category,
+            self.damage,
+            self.damage_type,
+            self.accuracy,
+            self.range_m,
+            self.rate_of_fire
+        )?;
+        if self.magazine_size > 0 {
+            write!(f, ", {}/{} shots", self.loaded(), self.magazine_size)?;
+        }
+        if let Some(caliber) = &self.caliber {
+            write!(f, " ({})", caliber)?;
+        }
+        Ok(())
+    }
+}
+
+impl Weapon {
+    pub fn new(
+        name: String,
+        category: WeaponCategory,
+        skill: String,
+        damage: DiceExpression,
+        damage_type: DamageType,
+    ) -> Self {
+        Weapon {
+            item: Item::new(None, name, 1, 0, 0, String::new()),
+            category,
+            skill,
+            damage,
+            damage_type,
+            caliber: None,
+            accuracy: 0,
+            range_m: 1,
+            rate_of_fire: 1,
+            magazine_size: 0,
+            reliability: Reliability::Standard,
+            concealability: Concealability::NotHideable,
+            availability: Availability::Common,
+            malfunction: None,
+            magazine: Vec::new(),
+        }
+    }
+
+    pub fn with_accuracy(mut self, accuracy: i32) -> Self {
+        self.accuracy = accuracy;
+        self
+    }
+
+    pub fn with_range(mut self, range_m: i32) -> Self {
+        self.range_m = range_m;
+        self
+    }
+
+    pub fn with_rate_of_fire(mut self, rate_of_fire: i32) -> Self {
+        self.rate_of_fire = rate_of_fire;
+        self
+    }
+
+    pub fn with_magazine(mut self, magazine_size: i32) -> Self {
+        self.magazine_size = magazine_size;
+        self
+    }
+
+    pub fn with_caliber(mut self, caliber: String) -> Self {
+        self.caliber = Some(caliber);
+        self
+    }
+
+    pub fn with_reliability(mut self, reliability: Reliability) -> Self {
+        self.reliability = reliability;
+        self
+    }
+
+    pub fn with_concealability(mut self, concealability: Concealability) -> Self {
+        self.concealability = concealability;
+        self
+    }
+
+    pub fn with_availability(mut self, availability: Availability) -> Self {
+        self.availability = availability;
+        self
+    }
+
+    /// Sets weight, price and comment of the underlying item.
+    pub fn with_item_details(mut self, weight_grams: i32, price_eb: i32, comment: String) -> Self {
+        self.item = Item::new(
+            Some(self.item.uuid),
+            self.item.name,
+            self.item.amount,
+            weight_grams,
+            price_eb,
+            comment,
+        );
+        self
+    }
+
+    /// Rounds currently loaded.
+    pub fn loaded(&self) -> i32 {
+        self.magazine.iter().map(|rounds| rounds.count).sum()
+    }
+
+    /// Loads up to `count` rounds of `ammunition` on top of the magazine
+    /// (the ammunition item itself is not touched). Returns how many fit.
+    pub fn load(&mut self, ammunition: &Ammunition, count: i32) -> Result<i32, String> {
+        if self.caliber.as_deref() != Some(ammunition.caliber.as_str()) {
+            return Err(format!(
+                "Ammunition '{}' ({}) doesn't fit '{}' ({})",
+                ammunition.item.name,
+                ammunition.caliber,
+                self.item.name,
+                self.caliber.as_deref().unwrap_or("no ammunition")
+            ));
+        }
+        let count = count.min(self.magazine_size - self.loaded()).max(0);
+        if count == 0 {
+            return Ok(0);
+        }
+        match self.magazine.last_mut() {
+            Some(top) if top.damage_type == ammunition.damage_type => top.count += count,
+            _ => self.magazine.push(LoadedRounds {
+                caliber: ammunition.caliber.clone(),
+                damage_type: ammunition.damage_type,
+                count,
+            }),
+        }
+        Ok(count)
+    }
+
+    /// Fails if the weapon has a malfunction that must be cleared first.
+    pub fn ready(&self) -> Result<(), String> {
+        match self.malfunction {
+            Some(malfunction) => Err(format!(
+                "Weapon '{}' can't fire: {}",
+                self.item.name, malfunction
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Fires `rounds` rounds from the top of the magazine and returns their
+    /// damage types in firing order. Fails without firing anything if the
+    /// weapon has a malfunction, holds fewer rounds, or rounds of another
+    /// caliber.
+    pub fn fire(&mut self, rounds: i32) -> Result<Vec<DamageType>, String> {
+        self.ready()?;
+        if self.loaded() == 0 {
+            return Err(format!("Weapon '{}' is empty", self.item.name));
+        }
+        if self.loaded() < rounds {
+            return Err(format!(
+                "Weapon '{}' has only {} rounds loaded, can't fire {}",
+                self.item.name,
+                self.loaded(),
+                rounds
+            ));
+        }
+        if let Some(wrong) = self
+            .magazine
+            .iter()
+            .find(|loaded| self.caliber.as_deref() != Some(loaded.caliber.as_str()))
+        {
+            return Err(format!(
+                "Weapon '{}' is loaded with {} rounds it can't fire",
+                self.item.name, wrong.caliber
+            ));
+        }
+        let mut fired = Vec::new();
+        while (fired.len() as i32) < rounds {
+            let top = self
+                .magazine
+                .last_mut()
+                .expect("enough rounds checked above");
+            top.count -= 1;
+            fired.push(top.damage_type);
+            if top.count == 0 {
+                self.magazine.pop();
+            }
+        }
+        Ok(fired)
+    }
+
+    /// Takes all rounds out of the magazine.
+    pub fn unload(&mut self) -> Vec<LoadedRounds> {
+        std::mem::take(&mut self.magazine)
+    }
+}
+
+/// Rounds of one caliber and type; the item amount is the exact number of
+/// rounds (ammunition doubles as currency), weight and price are per round.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Ammunition {
+    pub caliber: String,
+    pub damage_type: DamageType,
+    pub item: Item,
+}
+
+impl InventoryItem for Ammunition {
+    fn get_item(&self) -> &Item {
+        &self.item
+    }
+
+    fn get_item_mut(&mut self) -> &mut Item {
+        &mut self.item
+    }
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+    fn equals(&self, other: &dyn InventoryItem) -> bool {
+        if let Some(other_ammunition) = other.as_any().downcast_ref::<Ammunition>() {
+            self == other_ammunition
+        } else {
+            false
+        }
+    }
+}
+
+impl fmt::Display for Ammunition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.item, self.caliber, self.damage_type)
+    }
+}
+
+impl Ammunition {
+    pub fn new(
+        name: String,
+        caliber: String,
+        damage_type: DamageType,
+        count: i32,
+        weight_grams: i32,
+        price_eb: i32,
+    ) -> Self {
+        Ammunition {
+            item: Item::new(None, name, count, weight_grams, price_eb, String::new()),
+            caliber,
+            damage_type,
+        }
+    }
+
+    pub fn count(&self) -> i32 {
+        self.item.amount
+    }
+}
+
+/// The built-in catalog, see `data/weapons.toml`.
+const BUILTIN_CATALOG: &str = include_str!("../data/weapons.toml");
+
+/// One catalog weapon: the stats a [`Weapon`] is instantiated from.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub category: WeaponCategory,
+    pub skill: String,
+    pub damage: DiceExpression,
+    pub damage_type: DamageType,
+    #[serde(default)]
+    pub caliber: Option<String>,
+    pub accuracy: i32,
+    pub range_m: i32,
+    pub rate_of_fire: i32,
+    #[serde(default)]
+    pub magazine_size: i32,
+    pub reliability: Reliability,
+    pub concealability: Concealability,
+    pub availability: Availability,
+    pub weight_grams: i32,
+    pub price_eb: i32,
+    /// Best-guess stats or conflicting sources: check before relying on them.
+    #[serde(default)]
+    pub review: bool,
+    #[serde(default)]
+    pub note: String,
+}
+
+impl CatalogEntry {
+    /// A new weapon item (with its own UUID) with these stats.
+    pub fn instantiate(&self) -> Weapon {
+        let mut weapon = Weapon::new(
+            self.name.clone(),
+            self.category,
+            self.skill.clone(),
+            self.damage,
+            self.damage_type,
+        );
+        weapon.caliber = self.caliber.clone();
+        weapon
+            .with_accuracy(self.accuracy)
+            .with_range(self.range_m)
+            .with_rate_of_fire(self.rate_of_fire)
+            .with_magazine(self.magazine_size)
+            .with_reliability(self.reliability)
+            .with_concealability(self.concealability)
+            .with_availability(self.availability)
+            .with_item_details(self.weight_grams, self.price_eb, self.note.clone())
+    }
+}
+
+/// A list of weapons to instantiate by name, read from a TOML file with one
+/// `[[weapon]]` table per entry.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct WeaponCatalog {
+    #[serde(rename = "weapon")]
+    entries: Vec<CatalogEntry>,
+}
+
+impl WeaponCatalog {
+    /// The catalog shipped with the crate: one representative per weapon
+    /// category (Q27).
+    pub fn builtin() -> Self {
+        Self::from_toml(BUILTIN_CATALOG)
+            .unwrap_or_else(|error| panic!("Broken built-in weapon catalog: {}", error))
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let catalog: WeaponCatalog =
+            toml::from_str(text).map_err(|error| format!("Invalid weapon catalog: {}", error))?;
+        for (index, entry) in catalog.entries.iter().enumerate() {
+            if catalog.entries[..index]
+                .iter()
+                .any(|other| other.name == entry.name)
+            {
+                return Err(format!(
+                    "Weapon catalog lists '{}' more than once",
+                    entry.name
+                ));
+            }
+            for (field, value) in [
+                ("weight_grams", entry.weight_grams),
+                ("price_eb", entry.price_eb),
+                ("magazine_size", entry.magazine_size),
+            ] {
+                if value < 0 {
+                    return Err(format!(
+                        "Weapon catalog entry '{}': {} must not be negative, got {}",
+                        entry.name, field, value
+                    ));
+                }
+            }
+        }
+        Ok(catalog)
+    }
+
+    pub fn entries(&self) -> &[CatalogEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CatalogEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// A new weapon item of the catalog entry `name`.
+    pub fn weapon(&self, name: &str) -> Result<Weapon, String> {
+        self.get(name)
+            .map(CatalogEntry::instantiate)
+            .ok_or_else(|| format!("No weapon named '{}' in the catalog", name))
+    }
+
+    /// The entries flagged for review.
+    pub fn needs_review(&self) -> Vec<&CatalogEntry> {
+        self.entries.iter().filter(|entry| entry.review).collect()
+    }
+}
+
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use super::*;
 
+    pub fn ak_47() -> Weapon {
+        Weapon::new(
+            "AK-47".to_string(),
+            WeaponCategory::Rifle,
+            "Gewehr".to_string(),
+            "5d6".parse().unwrap(),
+            DamageType::Blunt,
+        )
+        .with_range(400)
+        .with_rate_of_fire(20)
+        .with_magazine(30)
+        .with_reliability(Reliability::VeryReliable)
+        .with_availability(Availability::Common)
+        .with_item_details(4500, 300, "7.62sov".to_string())
+    }
+
     #[test]
     fn test_damage_type_serialization() {
         let damage_type = DamageType::ArmorPiercing;
@@ -26,4 +622,137 @@ mod tests {
         let deserialized: DamageType = toml::from_str(&serialized).unwrap();
         assert_eq!(damage_type, deserialized);
     }
+
+    #[test]
+    fn test_weapon_serialization() {
+        let weapon = ak_47();
+        let serialized = toml::to_string(&weapon).unwrap();
+        assert!(serialized.contains("damage = \"5d6\""), "{}", serialized);
+        let deserialized: Weapon = toml::from_str(&serialized).unwrap();
+        assert_eq!(weapon, deserialized);
+    }
+
+    #[test]
+    fn test_builtin_catalog_covers_every_category() {
+        use std::collections::HashSet;
+        let catalog = WeaponCatalog::builtin();
+        let categories: HashSet<WeaponCategory> = catalog
+            .entries()
+            .iter()
+            .map(|entry| entry.category)
+            .collect();
+        assert_eq!(categories.len(), 14);
+        assert_eq!(catalog.entries().len(), 14);
+
+        let ak = catalog.weapon("AK-47").unwrap();
+        assert_eq!(ak.damage.to_string(), "5d6");
+        assert_eq!(ak.magazine_size, 30);
+        assert_eq!(ak.rate_of_fire, 20);
+        assert_eq!(ak.reliability, Reliability::VeryReliable);
+        assert_eq!(ak.range_m, 400);
+        assert_eq!(catalog.weapon("Grach").unwrap().damage.to_string(), "2d6+1");
+    }
+
+    #[test]
+    fn test_best_guesses_are_flagged_for_review() {
+        let catalog = WeaponCatalog::builtin();
+        let flagged: Vec<WeaponCategory> = catalog
+            .needs_review()
+            .iter()
+            .map(|entry| entry.category)
+            .collect();
+        for category in [
+            WeaponCategory::Sling,
+            WeaponCategory::Lance,
+            WeaponCategory::Molotov,
+        ] {
+            assert!(flagged.contains(&category), "{} not flagged", category);
+        }
+        assert!(!catalog.get("Combat Knife").unwrap().review);
+    }
+
+    #[test]
+    fn test_catalog_instantiates_separate_items() {
+        let catalog = WeaponCatalog::builtin();
+        let first = catalog.weapon("Combat Knife").unwrap();
+        let second = catalog.weapon("Combat Knife").unwrap();
+        assert_ne!(first.item.uuid, second.item.uuid);
+        assert_eq!(first.item.weight_grams, 300);
+        assert_eq!(
+            catalog.weapon("Lightsaber").unwrap_err(),
+            "No weapon named 'Lightsaber' in the catalog"
+        );
+    }
+
+    #[test]
+    fn test_catalog_rejects_duplicate_names() {
+        let entry = "[[weapon]]\nname = \"Club\"\ncategory = \"Club\"\nskill = \"Mittel\"\n\
+            damage = \"1d6\"\ndamage_type = \"Blunt\"\naccuracy = 0\nrange_m = 1\nrate_of_fire = 1\n\
+            reliability = \"VeryReliable\"\nconcealability = \"LongCoat\"\navailability = \"Common\"\n\
+            weight_grams = 1000\nprice_eb = 10\n";
+        assert!(WeaponCatalog::from_toml(entry).is_ok());
+        assert_eq!(
+            WeaponCatalog::from_toml(&format!("{}{}", entry, entry)).unwrap_err(),
+            "Weapon catalog lists 'Club' more than once"
+        );
+    }
+
+    #[test]
+    fn test_melee_weapons_use_their_class_specialization() {
+        for entry in WeaponCatalog::builtin().entries() {
+            if let Some(class) = entry.category.melee_class() {
+                assert_eq!(entry.skill, class.skill(), "{}", entry.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_weapon_defaults() {
+        let knife = Weapon::new(
+            "Combat Knife".to_string(),
+            WeaponCategory::Knife,
+            "Kurz".to_string(),
+            "1d6".parse().unwrap(),
+            DamageType::Slashing,
+        );
+        assert_eq!(knife.range_m, 1);
+        assert_eq!(knife.rate_of_fire, 1);
+        assert_eq!(knife.magazine_size, 0);
+        assert_eq!(knife.item.amount, 1);
+    }
+
+    #[test]
+    fn test_magazine_fires_last_loaded_rounds_first() {
+        let mut rifle = ak_47().with_caliber("7.62x39".to_string());
+        let regular = Ammunition::new(
+            "7.62x39".to_string(),
+            "7.62x39".to_string(),
+            DamageType::Blunt,
+            50,
+            10,
+            1,
+        );
+        let armor_piercing = Ammunition::new(
+            "7.62x39 AP".to_string(),
+            "7.62x39".to_string(),
+            DamageType::ArmorPiercing,
+            50,
+            10,
+            3,
+        );
+        assert_eq!(rifle.load(&regular, 20), Ok(20));
+        assert_eq!(rifle.load(&armor_piercing, 50), Ok(10));
+        assert_eq!(rifle.loaded(), 30);
+
+        let fired = rifle.fire(12).unwrap();
+        assert_eq!(fired[..10], [DamageType::ArmorPiercing; 10]);
+        assert_eq!(fired[10..], [DamageType::Blunt; 2]);
+        assert_eq!(rifle.loaded(), 18);
+        assert_eq!(
+            rifle.fire(19),
+            Err("Weapon 'AK-47' has only 18 rounds loaded, can't fire 19".to_string())
+        );
+        assert_eq!(rifle.unload().iter().map(|r| r.count).sum::<i32>(), 18);
+        assert_eq!(rifle.fire(1), Err("Weapon 'AK-47' is empty".to_string()));
+    }
 }